@@ -0,0 +1,221 @@
+use crate::data_model::{AppState, GuiHotkey};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheatSheetFormat {
+    Markdown,
+    Html,
+    Svg,
+}
+
+impl CheatSheetFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "md" | "markdown" => Some(Self::Markdown),
+            "html" | "htm" => Some(Self::Html),
+            "svg" => Some(Self::Svg),
+            _ => None,
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::from_name)
+    }
+}
+
+struct Section<'a> {
+    mode: &'a str,
+    hotkeys: Vec<&'a GuiHotkey>,
+}
+
+// Every mode with its active bindings, ordered by key and then modifiers so
+// variations of the same key end up next to each other.
+fn sections(state: &AppState) -> Vec<Section<'_>> {
+    state
+        .modes
+        .iter()
+        .map(|mode| {
+            let mut hotkeys: Vec<_> = mode
+                .hotkeys
+                .iter()
+                .filter(|hk| hk.action.active && !hk.key.is_empty())
+                .collect();
+            hotkeys.sort_by(|a, b| (&a.key, &a.modifiers).cmp(&(&b.key, &b.modifiers)));
+            Section {
+                mode: mode.name.trim(),
+                hotkeys,
+            }
+        })
+        .collect()
+}
+
+pub fn render(state: &AppState, format: CheatSheetFormat) -> String {
+    match format {
+        CheatSheetFormat::Markdown => render_markdown(state),
+        CheatSheetFormat::Html => render_html(state),
+        CheatSheetFormat::Svg => render_svg(state),
+    }
+}
+
+pub fn export(state: &AppState, path: &Path, format: CheatSheetFormat) -> Result<(), String> {
+    std::fs::write(path, render(state, format)).map_err(|e| e.to_string())
+}
+
+pub fn render_markdown(state: &AppState) -> String {
+    let mut out = String::from("# SWHKD Keybindings\n");
+    for section in sections(state) {
+        out.push_str(&format!("\n## {}\n\n", escape_markdown(section.mode)));
+        if section.hotkeys.is_empty() {
+            out.push_str("_No active bindings._\n");
+            continue;
+        }
+        out.push_str("| Keys | Description | Command |\n|---|---|---|\n");
+        for hk in section.hotkeys {
+            out.push_str(&format!(
+                "| {} | {} | {} |\n",
+                code_span(&hk.combo_string()),
+                escape_markdown(hk.action.description.as_deref().unwrap_or("")),
+                code_span(&hk.action.command),
+            ));
+        }
+    }
+    out
+}
+
+pub fn render_html(state: &AppState) -> String {
+    let mut out = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>SWHKD Keybindings</title>\n<style>\n\
+         body { font-family: sans-serif; margin: 2em; color: #1c1c1c; }\n\
+         h2 { border-bottom: 1px solid #dee3e8; padding-bottom: 4px; page-break-after: avoid; }\n\
+         table { border-collapse: collapse; width: 100%; margin-bottom: 1.5em; page-break-inside: avoid; }\n\
+         td, th { text-align: left; padding: 4px 8px; border-bottom: 1px solid #eef0f2; vertical-align: top; }\n\
+         kbd { font-family: monospace; background: #f2f4f7; border: 1px solid #dee3e8; border-radius: 4px; padding: 1px 5px; }\n\
+         code { font-size: 0.9em; color: #66738a; }\n\
+         </style>\n</head>\n<body>\n<h1>SWHKD Keybindings</h1>\n",
+    );
+    for section in sections(state) {
+        out.push_str(&format!("<h2>{}</h2>\n", escape_xml(section.mode)));
+        if section.hotkeys.is_empty() {
+            out.push_str("<p><em>No active bindings.</em></p>\n");
+            continue;
+        }
+        out.push_str("<table>\n<tr><th>Keys</th><th>Description</th><th>Command</th></tr>\n");
+        for hk in section.hotkeys {
+            let keys = hk
+                .combo_string()
+                .split(" + ")
+                .map(|k| format!("<kbd>{}</kbd>", escape_xml(k)))
+                .collect::<Vec<_>>()
+                .join(" + ");
+            out.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td><code>{}</code></td></tr>\n",
+                keys,
+                escape_xml(hk.action.description.as_deref().unwrap_or("")),
+                escape_xml(&hk.action.command),
+            ));
+        }
+        out.push_str("</table>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+// A4 width at 96 dpi, so the file prints (or converts to PDF) at 1:1.
+const SVG_WIDTH: u32 = 794;
+const SVG_MARGIN: u32 = 40;
+const SVG_LINE: u32 = 22;
+const SVG_MAX_COMMAND: usize = 48;
+
+pub fn render_svg(state: &AppState) -> String {
+    let mut body = String::new();
+    let mut y = SVG_MARGIN + 24;
+    body.push_str(&format!(
+        "<text x=\"{SVG_MARGIN}\" y=\"{y}\" font-size=\"24\" font-weight=\"bold\">SWHKD Keybindings</text>\n"
+    ));
+    y += SVG_LINE * 2;
+
+    for section in sections(state) {
+        body.push_str(&format!(
+            "<text x=\"{SVG_MARGIN}\" y=\"{y}\" font-size=\"18\" font-weight=\"bold\">{}</text>\n",
+            escape_xml(section.mode)
+        ));
+        body.push_str(&format!(
+            "<line x1=\"{SVG_MARGIN}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#dee3e8\"/>\n",
+            y + 6,
+            SVG_WIDTH - SVG_MARGIN,
+            y + 6
+        ));
+        y += SVG_LINE + 4;
+
+        if section.hotkeys.is_empty() {
+            body.push_str(&format!(
+                "<text x=\"{SVG_MARGIN}\" y=\"{y}\" font-style=\"italic\">No active bindings.</text>\n"
+            ));
+            y += SVG_LINE;
+        }
+        for hk in section.hotkeys {
+            body.push_str(&format!(
+                "<text x=\"{SVG_MARGIN}\" y=\"{y}\" font-family=\"monospace\">{}</text>\n",
+                escape_xml(&hk.combo_string())
+            ));
+            body.push_str(&format!(
+                "<text x=\"260\" y=\"{y}\">{}</text>\n",
+                escape_xml(hk.action.description.as_deref().unwrap_or(""))
+            ));
+            body.push_str(&format!(
+                "<text x=\"470\" y=\"{y}\" font-family=\"monospace\" fill=\"#66738a\">{}</text>\n",
+                escape_xml(&truncate(&hk.action.command, SVG_MAX_COMMAND))
+            ));
+            y += SVG_LINE;
+        }
+        y += SVG_LINE;
+    }
+
+    let height = y + SVG_MARGIN;
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{SVG_WIDTH}\" height=\"{height}\" viewBox=\"0 0 {SVG_WIDTH} {height}\" \
+         font-family=\"sans-serif\" font-size=\"13\" fill=\"#1c1c1c\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n{body}</svg>\n"
+    )
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        format!("{}…", s.chars().take(max - 1).collect::<String>())
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Plain table text: characters that would start emphasis, links, inline
+// HTML or code, or end the cell, are backslash-escaped.
+fn escape_markdown(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '\\' | '|' | '*' | '_' | '[' | ']' | '<' | '>' | '`' | '#') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+// A code span that can hold backticks: the fence is one backtick longer than
+// the longest run inside, with a space of padding when the text starts or
+// ends with one. `|` still needs escaping to stay inside the table cell.
+fn code_span(s: &str) -> String {
+    let longest = s.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest + 1);
+    let pad = if s.starts_with('`') || s.ends_with('`') { " " } else { "" };
+    format!("{fence}{pad}{}{pad}{fence}", s.replace('|', "\\|"))
+}
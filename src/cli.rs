@@ -0,0 +1,179 @@
+use crate::cheatsheet::{self, CheatSheetFormat};
+use crate::data_model::AppState;
+use crate::settings::{AppSettings, ReloadMethod, SettingsOverrides};
+use crate::{daemon, discovery, preflight, profiles};
+use std::path::PathBuf;
+
+const USAGE: &str = "\
+Usage: gui [OPTIONS] [COMMAND]
+
+Without a command the graphical configurator is started.
+
+Options (this run only, saved settings are left alone):
+  --config FILE          swhkdrc to read and write
+  --state FILE           GUI state file
+  --dialog-dir DIR       Directory file dialogs start in
+  --reload-method auto|systemd|signal|pkexec|manual
+  --backups N            Config backups to keep, 0 to disable
+
+Commands:
+  cheatsheet [--format markdown|html|svg] [--state FILE] [--output FILE]
+      Render every mode and its active hotkeys as a printable cheat sheet.
+      The format defaults to the output file's extension, or markdown.
+  apply [--profile NAME]
+      Write the GUI state, or the named profile, to swhkdrc and reload
+      swhkd. Profiles are read from the profiles directory next to the
+      state file.
+  schema [--output FILE]
+      Print the JSON Schema of the GUI state file. State files are checked
+      against it when they are loaded.
+  help
+      Show this message.
+";
+
+// Splits the leading global options off `args`, returning them and the
+// command with its own arguments.
+pub fn parse_overrides(args: &[String]) -> Result<(SettingsOverrides, Vec<String>), String> {
+    let mut overrides = SettingsOverrides::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.as_slice().first() {
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        if !matches!(name, "--config" | "--state" | "--dialog-dir" | "--reload-method" | "--backups") {
+            break;
+        }
+        args.next();
+        let value = match inline {
+            Some(value) => value,
+            None => args.next().cloned().ok_or(format!("{name} expects a value"))?,
+        };
+        match name {
+            "--config" => overrides.config_path = Some(PathBuf::from(value)),
+            "--state" => overrides.state_path = Some(PathBuf::from(value)),
+            "--dialog-dir" => overrides.dialog_dir = Some(PathBuf::from(value)),
+            "--reload-method" => {
+                overrides.reload_method = Some(ReloadMethod::from_name(&value).ok_or(format!("Unknown reload method '{value}'"))?);
+            }
+            _ => overrides.backup_count = Some(value.parse().map_err(|_| format!("--backups expects a number, got '{value}'"))?),
+        }
+    }
+    Ok((overrides, args.cloned().collect()))
+}
+
+// Returns the process exit code.
+pub fn run(args: &[String], settings: &AppSettings) -> i32 {
+    let result = match args.first().map(String::as_str) {
+        Some("cheatsheet") => cheatsheet_command(&args[1..], settings),
+        Some("apply") => apply_command(&args[1..], settings),
+        Some("schema") => schema_command(&args[1..]),
+        Some("help") | Some("--help") | Some("-h") => {
+            print!("{USAGE}");
+            Ok(())
+        }
+        Some(other) => Err(format!("Unknown command '{other}'\n\n{USAGE}")),
+        None => Ok(()),
+    };
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{e}");
+            1
+        }
+    }
+}
+
+fn cheatsheet_command(args: &[String], settings: &AppSettings) -> Result<(), String> {
+    let mut format = None;
+    let mut state_path = settings.state_path.display().to_string();
+    let mut output = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().cloned().ok_or(format!("{arg} expects a value"));
+        match arg.as_str() {
+            "--format" | "-f" => {
+                let name = value()?;
+                format = Some(CheatSheetFormat::from_name(&name).ok_or(format!("Unknown format '{name}'"))?);
+            }
+            "--state" | "-s" => state_path = value()?,
+            "--output" | "-o" => output = Some(PathBuf::from(value()?)),
+            other => return Err(format!("Unexpected argument '{other}'\n\n{USAGE}")),
+        }
+    }
+
+    let format = format
+        .or_else(|| output.as_deref().and_then(CheatSheetFormat::from_path))
+        .unwrap_or(CheatSheetFormat::Markdown);
+    let state = AppState::load_from_json_file(&state_path)?;
+
+    match output {
+        Some(path) => cheatsheet::export(&state, &path, format),
+        None => {
+            print!("{}", cheatsheet::render(&state, format));
+            Ok(())
+        }
+    }
+}
+
+fn apply_command(args: &[String], settings: &AppSettings) -> Result<(), String> {
+    let mut profile = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--profile" | "-p" => profile = Some(args.next().cloned().ok_or(format!("{arg} expects a value"))?),
+            other => return Err(format!("Unexpected argument '{other}'\n\n{USAGE}")),
+        }
+    }
+
+    let mut state = match &profile {
+        Some(name) => profiles::load(&profiles::profiles_dir(&settings.state_path), name)?,
+        None => AppState::load_from_json_file(&settings.state_path.display().to_string())?,
+    };
+    state.check_duplicate_bindings()?;
+    // `settings` already carries this run's overrides.
+    let config = discovery::locate(settings, &SettingsOverrides::default());
+    // Keep the config's `include` lines, as the GUI does.
+    let mut on_disk = AppState::default();
+    if on_disk.load_from_swhkd_config_at(&config.path.display().to_string()).is_ok() {
+        state.includes = on_disk.includes;
+    }
+    let text = state.to_swhkd_config_text();
+    preflight::check_config_text(&text, &config.path, preflight::configured_checker(settings).as_deref())?;
+    if discovery::needs_privilege(&config.path) {
+        discovery::write_privileged(&config.path, &text)?;
+    } else {
+        state.backup_config(&config.path, settings.backup_count)?;
+        state.save_to_custom_path(&config.path.display().to_string())?;
+    }
+    println!("Wrote {}", config.path.display());
+
+    let report = daemon::reload(settings.reload_method);
+    if !report.success {
+        return Err(report.summary());
+    }
+    println!("{}", report.summary());
+    Ok(())
+}
+
+fn schema_command(args: &[String]) -> Result<(), String> {
+    let mut output = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output" | "-o" => output = Some(args.next().cloned().ok_or(format!("{arg} expects a value"))?),
+            other => return Err(format!("Unexpected argument '{other}'\n\n{USAGE}")),
+        }
+    }
+
+    let schema = serde_json::to_string_pretty(&AppState::json_schema()).map_err(|e| e.to_string())?;
+    match output {
+        Some(path) => std::fs::write(&path, schema + "\n").map_err(|e| format!("Could not write {path}: {e}")),
+        None => {
+            println!("{schema}");
+            Ok(())
+        }
+    }
+}
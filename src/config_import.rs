@@ -0,0 +1,150 @@
+use crate::data_model::{AppMode, AppState};
+use std::collections::HashMap;
+
+// What happens to an imported binding whose combo is already bound to an
+// active hotkey in the target mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictPolicy {
+    #[default]
+    Skip,
+    Overwrite,
+    // Add the imported binding as an inactive row next to the existing one.
+    KeepBoth,
+}
+
+impl ConflictPolicy {
+    pub const ALL: [ConflictPolicy; 3] = [ConflictPolicy::Skip, ConflictPolicy::Overwrite, ConflictPolicy::KeepBoth];
+}
+
+impl std::fmt::Display for ConflictPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ConflictPolicy::Skip => "Skip conflicting bindings",
+            ConflictPolicy::Overwrite => "Overwrite existing bindings",
+            ConflictPolicy::KeepBoth => "Keep both, import as inactive",
+        })
+    }
+}
+
+// Where the hotkeys of one mode in the imported file go.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportTarget {
+    Existing(usize),
+    // A new mode; the name is made unique when the import is applied.
+    New(String),
+    Ignore,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportOutcome {
+    Added,
+    // The same combo and command are already there.
+    AlreadyPresent,
+    Skipped,
+    Overwrote,
+    AddedInactive,
+}
+
+impl std::fmt::Display for ImportOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ImportOutcome::Added => "added",
+            ImportOutcome::AlreadyPresent => "already present",
+            ImportOutcome::Skipped => "skipped, combo in use",
+            ImportOutcome::Overwrote => "overwrites existing",
+            ImportOutcome::AddedInactive => "added inactive",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportRow {
+    pub mode: String,
+    pub combo: String,
+    pub command: String,
+    pub outcome: ImportOutcome,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportResult {
+    pub modes: Vec<AppMode>,
+    pub rows: Vec<ImportRow>,
+}
+
+// The modes of an imported file and what to do with each of them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportPlan {
+    pub modes: Vec<AppMode>,
+    pub targets: Vec<ImportTarget>,
+    pub policy: ConflictPolicy,
+}
+
+impl ImportPlan {
+    // Modes are matched to existing ones by name; the rest become new modes.
+    pub fn new(imported: Vec<AppMode>, existing: &[AppMode]) -> Self {
+        let targets = imported
+            .iter()
+            .map(|mode| {
+                match existing.iter().position(|m| m.name.trim().eq_ignore_ascii_case(mode.name.trim())) {
+                    Some(idx) => ImportTarget::Existing(idx),
+                    None => ImportTarget::New(mode.name.trim().to_string()),
+                }
+            })
+            .collect();
+        Self { modes: imported, targets, policy: ConflictPolicy::default() }
+    }
+
+    // The modes of `state` with the import applied, and what happened to
+    // every imported binding. Nothing is changed until the caller stores
+    // the returned modes.
+    pub fn apply(&self, state: &AppState) -> ImportResult {
+        let mut result = state.clone();
+        let mut new_modes: HashMap<&str, usize> = HashMap::new();
+        let mut rows = Vec::new();
+
+        for (mode, target) in self.modes.iter().zip(&self.targets) {
+            let idx = match target {
+                ImportTarget::Ignore => continue,
+                ImportTarget::Existing(idx) if *idx < result.modes.len() => *idx,
+                ImportTarget::Existing(_) => continue,
+                ImportTarget::New(name) => *new_modes
+                    .entry(name.as_str())
+                    .or_insert_with(|| result.add_mode(name, Vec::new())),
+            };
+            let target = &mut result.modes[idx];
+            for hk in &mode.hotkeys {
+                let existing = target
+                    .hotkeys
+                    .iter()
+                    .position(|other| other.action.active && hk.action.active && other.signature() == hk.signature());
+                let outcome = match existing {
+                    Some(i) if target.hotkeys[i].action.command == hk.action.command => ImportOutcome::AlreadyPresent,
+                    Some(i) => match self.policy {
+                        ConflictPolicy::Skip => ImportOutcome::Skipped,
+                        ConflictPolicy::Overwrite => {
+                            target.hotkeys[i] = hk.clone();
+                            ImportOutcome::Overwrote
+                        }
+                        ConflictPolicy::KeepBoth => {
+                            let mut inactive = hk.clone();
+                            inactive.action.active = false;
+                            target.hotkeys.push(inactive);
+                            ImportOutcome::AddedInactive
+                        }
+                    },
+                    None => {
+                        target.hotkeys.push(hk.clone());
+                        ImportOutcome::Added
+                    }
+                };
+                rows.push(ImportRow {
+                    mode: target.name.clone(),
+                    combo: hk.combo_string(),
+                    command: hk.action.command.clone(),
+                    outcome,
+                });
+            }
+        }
+        ImportResult { modes: result.modes, rows }
+    }
+}
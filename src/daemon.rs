@@ -0,0 +1,295 @@
+use crate::discovery::SYSTEM_CONFIG;
+use crate::settings::ReloadMethod;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::SystemTime;
+
+const SYSTEMD_UNIT: &str = "swhkd.service";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DaemonMode {
+    // A `systemctl --user` unit manages swhkd.
+    SystemdUnit(String),
+    // swhkd runs as the current user and can be signalled directly.
+    Process(u32),
+    // swhkd runs as root next to the per-user swhks server, so signalling it
+    // needs elevated rights.
+    SwhksServer { swhkd_pid: u32 },
+    // swhkd runs as another user without swhks, e.g. a system service.
+    Privileged(u32),
+    NotRunning,
+}
+
+impl std::fmt::Display for DaemonMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DaemonMode::SystemdUnit(unit) => write!(f, "systemd user unit {unit}"),
+            DaemonMode::Process(pid) => write!(f, "process {pid}"),
+            DaemonMode::SwhksServer { swhkd_pid } => write!(f, "root process {swhkd_pid} with swhks"),
+            DaemonMode::Privileged(pid) => write!(f, "root process {pid}"),
+            DaemonMode::NotRunning => write!(f, "not running"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub uid: Option<u32>,
+    pub cmdline: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReloadReport {
+    pub mode: DaemonMode,
+    pub method: String,
+    pub success: bool,
+    pub stderr: String,
+}
+
+impl ReloadReport {
+    pub fn summary(&self) -> String {
+        if self.success && self.method == "none" {
+            "Saved; swhkd was not reloaded".to_string()
+        } else if self.success {
+            format!("swhkd reloaded ({}) via `{}`", self.mode, self.method)
+        } else if self.stderr.trim().is_empty() {
+            format!("swhkd reload failed ({}) via `{}`", self.mode, self.method)
+        } else {
+            format!("swhkd reload failed ({}) via `{}`: {}", self.mode, self.method, self.stderr.trim())
+        }
+    }
+}
+
+pub fn find_processes(name: &str) -> Vec<ProcessInfo> {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    let mut processes: Vec<ProcessInfo> = entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+        .filter(|pid| {
+            fs::read_to_string(format!("/proc/{pid}/comm"))
+                .map(|comm| comm.trim() == name)
+                .unwrap_or(false)
+        })
+        .map(|pid| ProcessInfo {
+            pid,
+            uid: process_uid(&pid.to_string()),
+            cmdline: fs::read(format!("/proc/{pid}/cmdline"))
+                .map(|raw| {
+                    raw.split(|b| *b == 0)
+                        .filter(|arg| !arg.is_empty())
+                        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+        .collect();
+    processes.sort_by_key(|p| p.pid);
+    processes
+}
+
+// Real uid from `/proc/<pid>/status`; `self` works for the GUI itself.
+fn process_uid(pid: &str) -> Option<u32> {
+    let status = fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Uid:"))
+        .and_then(|ids| ids.split_whitespace().next())
+        .and_then(|uid| uid.parse().ok())
+}
+
+fn systemd_unit_active(unit: &str) -> bool {
+    Command::new("systemctl")
+        .args(["--user", "is-active", "--quiet", unit])
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+pub fn detect() -> DaemonMode {
+    if systemd_unit_active(SYSTEMD_UNIT) {
+        return DaemonMode::SystemdUnit(SYSTEMD_UNIT.to_string());
+    }
+
+    let Some(swhkd) = find_processes("swhkd").into_iter().next() else {
+        return DaemonMode::NotRunning;
+    };
+    if swhkd.uid.is_some() && swhkd.uid == process_uid("self") {
+        DaemonMode::Process(swhkd.pid)
+    } else if !find_processes("swhks").is_empty() {
+        DaemonMode::SwhksServer { swhkd_pid: swhkd.pid }
+    } else {
+        DaemonMode::Privileged(swhkd.pid)
+    }
+}
+
+// What `reload` does for a method, given how swhkd runs and its pid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReloadAction {
+    // Only the file is written.
+    Skip,
+    NotRunning,
+    Run(Vec<String>),
+}
+
+fn sighup(pid: u32) -> Vec<String> {
+    vec!["kill".into(), "-HUP".into(), pid.to_string()]
+}
+
+fn pkexec_sighup(pid: u32) -> Vec<String> {
+    vec!["pkexec".into(), "kill".into(), "-HUP".into(), pid.to_string()]
+}
+
+fn systemd_reload(unit: &str) -> Vec<String> {
+    vec!["systemctl".into(), "--user".into(), "reload-or-restart".into(), unit.into()]
+}
+
+// swhkd re-reads its config on SIGHUP (SIGUSR1/SIGUSR2 pause and resume it).
+// Any method other than `Auto` is used as configured, whatever `detect` finds.
+pub fn reload_action(method: ReloadMethod, mode: &DaemonMode, pid: Option<u32>) -> ReloadAction {
+    match (method, mode, pid) {
+        (ReloadMethod::Manual, _, _) => ReloadAction::Skip,
+        (ReloadMethod::Systemd, _, _) => ReloadAction::Run(systemd_reload(SYSTEMD_UNIT)),
+        (ReloadMethod::Signal, _, Some(pid)) => ReloadAction::Run(sighup(pid)),
+        (ReloadMethod::Pkexec, _, Some(pid)) => ReloadAction::Run(pkexec_sighup(pid)),
+        (ReloadMethod::Signal | ReloadMethod::Pkexec, _, None) => ReloadAction::NotRunning,
+        (ReloadMethod::Auto, DaemonMode::SystemdUnit(unit), _) => ReloadAction::Run(systemd_reload(unit)),
+        (ReloadMethod::Auto, DaemonMode::Process(pid), _) => ReloadAction::Run(sighup(*pid)),
+        (ReloadMethod::Auto, DaemonMode::SwhksServer { swhkd_pid: pid } | DaemonMode::Privileged(pid), _) => {
+            ReloadAction::Run(pkexec_sighup(*pid))
+        }
+        (ReloadMethod::Auto, DaemonMode::NotRunning, _) => ReloadAction::NotRunning,
+    }
+}
+
+// Blocks on `systemctl` and the reload command; run it off the UI thread.
+pub fn reload(method: ReloadMethod) -> ReloadReport {
+    let mode = detect();
+    let pid = match &mode {
+        DaemonMode::Process(pid) | DaemonMode::SwhksServer { swhkd_pid: pid } | DaemonMode::Privileged(pid) => Some(*pid),
+        DaemonMode::SystemdUnit(_) | DaemonMode::NotRunning => find_processes("swhkd").first().map(|p| p.pid),
+    };
+    match reload_action(method, &mode, pid) {
+        ReloadAction::Skip => ReloadReport {
+            mode,
+            method: "none".to_string(),
+            success: true,
+            stderr: String::new(),
+        },
+        ReloadAction::NotRunning => not_running(mode),
+        ReloadAction::Run(command) => run_reload(mode, &command),
+    }
+}
+
+fn not_running(mode: DaemonMode) -> ReloadReport {
+    ReloadReport {
+        mode,
+        method: "none".to_string(),
+        success: false,
+        stderr: "swhkd is not running; start it to use the saved config".to_string(),
+    }
+}
+
+fn run_reload(mode: DaemonMode, command: &[String]) -> ReloadReport {
+    let method = command.join(" ");
+    match Command::new(&command[0]).args(&command[1..]).output() {
+        Ok(output) => ReloadReport {
+            mode,
+            method,
+            success: output.status.success(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        },
+        Err(e) => ReloadReport {
+            mode,
+            method,
+            success: false,
+            stderr: e.to_string(),
+        },
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigState {
+    // The GUI hasn't written this file during the session.
+    NotSaved,
+    Matches,
+    // Changed on disk after the GUI last saved it.
+    Modified,
+    // swhkd loaded a different file than the one the GUI writes.
+    OtherFile,
+    Missing,
+}
+
+#[derive(Debug, Clone)]
+pub struct DaemonStatus {
+    pub swhkd: Option<ProcessInfo>,
+    pub swhks: Option<ProcessInfo>,
+    pub config_path: PathBuf,
+    pub config_modified: Option<SystemTime>,
+    pub config_state: ConfigState,
+}
+
+// What the GUI last wrote: the target path and a hash of the text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedConfig {
+    pub path: PathBuf,
+    pub hash: u64,
+}
+
+pub fn content_hash(text: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+// The config swhkd was started with, falling back to its built-in default.
+pub fn loaded_config_path(swhkd: &ProcessInfo) -> PathBuf {
+    let mut args = swhkd.cmdline.iter().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "-c" || arg == "--config" {
+            if let Some(path) = args.next() {
+                return PathBuf::from(path);
+            }
+        } else if let Some(path) = arg.strip_prefix("--config=") {
+            return PathBuf::from(path);
+        }
+    }
+    PathBuf::from(SYSTEM_CONFIG)
+}
+
+// How the config swhkd loaded compares with what the GUI last wrote.
+pub fn config_state(config_path: &Path, gui_config: &Path, last_saved: Option<&SavedConfig>) -> ConfigState {
+    match fs::read(config_path) {
+        Err(_) => ConfigState::Missing,
+        Ok(_) if config_path != gui_config => ConfigState::OtherFile,
+        Ok(contents) => match last_saved {
+            Some(saved) if saved.path == config_path && saved.hash == content_hash(&contents) => ConfigState::Matches,
+            Some(saved) if saved.path == config_path => ConfigState::Modified,
+            _ => ConfigState::NotSaved,
+        },
+    }
+}
+
+pub fn status(gui_config: &Path, last_saved: Option<&SavedConfig>) -> DaemonStatus {
+    let swhkd = find_processes("swhkd").into_iter().next();
+    let swhks = find_processes("swhks").into_iter().next();
+    let config_path = swhkd
+        .as_ref()
+        .map(loaded_config_path)
+        .unwrap_or_else(|| gui_config.to_path_buf());
+    let config_modified = fs::metadata(&config_path).and_then(|m| m.modified()).ok();
+    let config_state = config_state(&config_path, gui_config, last_saved);
+
+    DaemonStatus {
+        swhkd,
+        swhks,
+        config_path,
+        config_modified,
+        config_state,
+    }
+}
@@ -1,80 +1,403 @@
 use sweet::{Key, Modifier};
 use evdev::Key as EvdevKey;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeSet, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::fs;
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
 use std::fs::File;
 use std::io::Write;
 
 
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct GuiAction {
     pub command: String,
     pub active: bool,
     pub layer_id: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    // Only set for this command; swhkdrc gets an `env ... sh -c` wrapper.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<EnvVar>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+pub struct EnvVar {
+    pub name: String,
+    pub value: String,
+}
+
+impl Default for GuiAction {
+    fn default() -> Self {
+        Self {
+            command: String::new(),
+            active: true,
+            layer_id: 0,
+            description: None,
+            tags: Vec::new(),
+            env: Vec::new(),
+            working_dir: None,
+        }
+    }
+}
+
+impl GuiAction {
+    // The command line as swhkd runs it, with the environment and working
+    // directory applied.
+    pub fn shell_command(&self) -> String {
+        wrap_command(&self.command, &self.env, self.working_dir.as_deref())
+    }
+}
+
+fn is_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// Double quotes keep `$VAR` expanding, so values like `$HOME/bin:$PATH` work.
+fn double_quote(text: &str) -> String {
+    if !text.is_empty() && text.chars().all(|c| c.is_ascii_alphanumeric() || "_-./:,@%+=".contains(c)) {
+        return text.to_string();
+    }
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' | '\\' | '`' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+// `env A="1" B=2 sh -c 'cd "dir" && command'`. Either part is left out when
+// unused; without both the command is written as it is. Variables without a
+// valid name are dropped, `validation_error` reports them. `~` doesn't expand
+// in double quotes, so a leading one becomes `$HOME`.
+pub fn wrap_command(command: &str, env: &[EnvVar], working_dir: Option<&str>) -> String {
+    let env: Vec<&EnvVar> = env.iter().filter(|var| is_env_name(var.name.trim())).collect();
+    let working_dir = working_dir.map(str::trim).filter(|dir| !dir.is_empty());
+    if env.is_empty() && working_dir.is_none() {
+        return command.to_string();
+    }
+    let script = match working_dir {
+        Some(dir) => {
+            let dir = match dir.strip_prefix('~') {
+                Some(rest) if rest.is_empty() || rest.starts_with('/') => format!("$HOME{rest}"),
+                _ => dir.to_string(),
+            };
+            format!("cd {} && {}", double_quote(&dir), command)
+        }
+        None => command.to_string(),
+    };
+    let mut line = String::new();
+    if !env.is_empty() {
+        line.push_str("env ");
+        for var in env {
+            line.push_str(&format!("{}={} ", var.name.trim(), double_quote(&var.value)));
+        }
+    }
+    line.push_str(&format!("sh -c '{}'", script.replace('\'', "'\\''")));
+    line
+}
+
+// The reverse of `wrap_command`. Lines in any other form, including a plain
+// `sh -c '...'`, are returned as the command.
+pub fn unwrap_command(line: &str) -> (String, Vec<EnvVar>, Option<String>) {
+    parse_wrapped(line).unwrap_or_else(|| (line.to_string(), Vec::new(), None))
+}
+
+fn parse_wrapped(line: &str) -> Option<(String, Vec<EnvVar>, Option<String>)> {
+    let mut rest = line.trim();
+    let mut env = Vec::new();
+    if let Some(after) = rest.strip_prefix("env ") {
+        rest = after.trim_start();
+        while let Some((name, after)) = rest.split_once('=').filter(|(name, _)| is_env_name(name)) {
+            let (value, after) = read_word(after)?;
+            env.push(EnvVar { name: name.to_string(), value });
+            rest = after.trim_start();
+        }
+        if env.is_empty() {
+            return None;
+        }
+    }
+    let script = read_single_quoted(rest.strip_prefix("sh -c ")?)?;
+    let (working_dir, command) = match script.strip_prefix("cd ") {
+        Some(after) => {
+            let (dir, after) = read_word(after)?;
+            (Some(dir), after.strip_prefix(" && ")?.to_string())
+        }
+        None => (None, script),
+    };
+    if env.is_empty() && working_dir.is_none() {
+        return None;
+    }
+    Some((command, env, working_dir))
+}
+
+// One double-quoted or bare word, and the text after it.
+fn read_word(text: &str) -> Option<(String, &str)> {
+    let Some(quoted) = text.strip_prefix('"') else {
+        let end = text.find(char::is_whitespace).unwrap_or(text.len());
+        return Some((text[..end].to_string(), &text[end..]));
+    };
+    let mut word = String::new();
+    let mut chars = quoted.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => {
+                let rest = &quoted[i + 1..];
+                return (rest.is_empty() || rest.starts_with(char::is_whitespace)).then_some((word, rest));
+            }
+            '\\' => match chars.next() {
+                Some((_, escaped @ ('"' | '\\' | '`' | '$'))) => word.push(escaped),
+                Some((_, other)) => {
+                    word.push('\\');
+                    word.push(other);
+                }
+                None => return None,
+            },
+            _ => word.push(c),
+        }
+    }
+    None
+}
+
+// All of `text` as one single-quoted word, where `'\''` stands for a quote.
+fn read_single_quoted(text: &str) -> Option<String> {
+    let mut word = String::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("\\'") {
+            word.push('\'');
+            rest = after;
+        } else {
+            let after = rest.strip_prefix('\'')?;
+            let end = after.find('\'')?;
+            word.push_str(&after[..end]);
+            rest = &after[end + 1..];
+        }
+    }
+    (!text.is_empty()).then_some(word)
+}
+
+const DESCRIPTION_COMMENT: &str = "# description: ";
+const TAGS_COMMENT: &str = "# tags: ";
+
+// Tags are stored comma separated in swhkdrc, so commas can't be part of one.
+pub fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    for tag in text.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        if !tags.iter().any(|t: &String| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+// Identifies a hotkey or mode while the GUI runs, so Undo can find it again
+// after later edits. Ids are never saved; loading hands out fresh ones.
+pub fn new_id() -> u64 {
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GuiHotkey {
+    #[serde(skip, default = "new_id")]
+    pub id: u64,
     pub modifiers: BTreeSet<String>,
     pub key: String,
     pub action: GuiAction,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// Two hotkeys are equal when they bind the same thing, whatever their ids.
+impl PartialEq for GuiHotkey {
+    fn eq(&self, other: &Self) -> bool {
+        self.modifiers == other.modifiers && self.key == other.key && self.action == other.action
+    }
+}
+
+impl GuiHotkey {
+    pub fn new(modifiers: BTreeSet<String>, key: String, action: GuiAction) -> Self {
+        Self { id: new_id(), modifiers, key, action }
+    }
+
+    // A copy that Undo tells apart from the original.
+    pub fn fresh_copy(&self) -> Self {
+        Self { id: new_id(), ..self.clone() }
+    }
+
+    // Case-insensitive match against the combo, command, description and tags.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return true;
+        }
+        self.combo_string().to_lowercase().contains(&query)
+            || self.action.command.to_lowercase().contains(&query)
+            || self
+                .action
+                .description
+                .as_deref()
+                .is_some_and(|d| d.to_lowercase().contains(&query))
+            || self.action.tags.iter().any(|t| t.to_lowercase().contains(&query))
+    }
+
+    pub fn validation_error(&self) -> Option<String> {
+        if self.key.trim().is_empty() {
+            return Some("No key recorded".to_string());
+        }
+        if self.key.contains(char::is_whitespace) || self.key.contains('+') {
+            return Some(format!("Invalid key '{}'", self.key));
+        }
+        if let Some(m) = self.modifiers.iter().find(|m| string_to_modifier(m).is_none()) {
+            return Some(format!("Unknown modifier '{}'", m));
+        }
+        if self.action.command.trim().is_empty() {
+            return Some("No command".to_string());
+        }
+        if let Some(var) = self.action.env.iter().find(|var| !is_env_name(var.name.trim())) {
+            return Some(format!("Invalid environment variable name '{}'", var.name));
+        }
+        // swhkdrc commands are a single line.
+        if let Some(var) = self.action.env.iter().find(|var| var.value.contains('\n')) {
+            return Some(format!("The value of {} has a line break", var.name.trim()));
+        }
+        if self.action.working_dir.as_deref().is_some_and(|dir| dir.contains('\n')) {
+            return Some("The working directory has a line break".to_string());
+        }
+        None
+    }
+
+    pub fn signature(&self) -> (BTreeSet<String>, String) {
+        (self.modifiers.clone(), self.key.clone())
+    }
+
+    pub fn combo_string(&self) -> String {
+        if self.modifiers.is_empty() {
+            self.key.clone()
+        } else {
+            format!(
+                "{} + {}",
+                self.modifiers.iter().cloned().collect::<Vec<_>>().join(" + "),
+                self.key
+            )
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AppMode {
+    #[serde(skip, default = "new_id")]
+    pub id: u64,
     pub name: String,
     pub hotkeys: Vec<GuiHotkey>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl PartialEq for AppMode {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.hotkeys == other.hotkeys
+    }
+}
+
+impl AppMode {
+    pub fn new(name: String, hotkeys: Vec<GuiHotkey>) -> Self {
+        Self { id: new_id(), name, hotkeys }
+    }
+}
+
+// Layout version of the JSON state file. Bump it together with a new entry
+// in `STATE_MIGRATIONS` whenever the saved layout changes.
+pub const STATE_VERSION: u32 = 2;
+
+// An `include`/`import` line of the loaded config and the mode block it was
+// in; `None` is outside any block, where the "Default" mode is written.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Include {
+    pub mode: Option<String>,
+    pub line: String,
+}
+
+fn is_default_mode(name: &str) -> bool {
+    name.trim().eq_ignore_ascii_case("default")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AppState {
+    pub version: u32,
     pub modes: Vec<AppMode>,
     pub selected_mode: usize,
     pub recording_hotkey: Option<usize>,
     pub last_backup: Option<PathBuf>,
+    // `include`/`import` lines of the loaded config, written back where they
+    // were; the GUI doesn't edit the files they name.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub includes: Vec<Include>,
 }
 
 impl Default for AppState {
     fn default() -> Self {
         Self {
-            modes: vec![AppMode {
-                name: "Default".to_string(),
-                hotkeys: Vec::new(),
-            }],
+            version: STATE_VERSION,
+            modes: vec![AppMode::new("Default".to_string(), Vec::new())],
             selected_mode: 0,
             recording_hotkey: None,
             last_backup: None,
+            includes: Vec::new(),
         }
     }
 }
 
-impl AppState {
-    
-    pub fn load_swhkd_config(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-    let config_path = self.get_swhkd_config_path()?;
-    self.modes.clear();
-
-    let mut hotkeys = Vec::new();
-
-    if config_path.exists() {
-        let contents = fs::read_to_string(&config_path)?;
-
-        let mut lines = contents.lines().peekable();
-
-        while let Some(line) = lines.next() {
-            let line = line.trim_end();
-
-            if line.is_empty() || line.starts_with('#') {
-                continue;
-            }
+// `STATE_MIGRATIONS[n]` upgrades a version `n + 1` state to `n + 2`.
+const STATE_MIGRATIONS: [fn(&mut serde_json::Value) -> Result<(), String>; STATE_VERSION as usize - 1] = [migrate_v1_to_v2];
 
+// Version 1 wrote modes as `@<name>` headers, which took any text as the
+// name; version 2 writes swhkd `mode <name>` blocks, where the name is one
+// word. Names are trimmed and their spaces become underscores, keeping them
+// unique. Version 1 also saved the hotkey being recorded, which points at a
+// stale row after a restart.
+fn migrate_v1_to_v2(state: &mut serde_json::Value) -> Result<(), String> {
+    let modes = state
+        .get_mut("modes")
+        .and_then(|m| m.as_array_mut())
+        .ok_or("modes: expected an array")?;
+    let mut taken: Vec<String> = Vec::new();
+    for (i, mode) in modes.iter_mut().enumerate() {
+        let name = mode
+            .get("name")
+            .and_then(|n| n.as_str())
+            .ok_or(format!("modes/{i}/name: expected a string"))?;
+        let base = name.split_whitespace().collect::<Vec<_>>().join("_");
+        let is_taken = |name: &str| taken.iter().any(|t| t.eq_ignore_ascii_case(name));
+        let name = if is_taken(&base) {
+            (2..).map(|n| format!("{base}_{n}")).find(|name| !is_taken(name)).unwrap()
+        } else {
+            base
+        };
+        mode["name"] = name.clone().into();
+        taken.push(name);
+    }
+    state["recording_hotkey"] = serde_json::Value::Null;
+    Ok(())
+}
 
 impl AppState {
     pub fn save_to_json_file(&self, path: &str) -> Result<(), String> {
+        if let Some(parent) = Path::new(path).parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
         match serde_json::to_string_pretty(self) {
             Ok(json) => {
                 File::create(path)
@@ -84,168 +407,141 @@ impl AppState {
             Err(e) => Err(e.to_string()),
         }
     }
-}
-
-            
-            if line.starts_with('@') {
-                continue; 
-            }
-
-            
-            if !line.starts_with(' ') && !line.starts_with('\t') {
-                let hotkey_line = line;
-
-               
-                let mut command_line = None;
-                while let Some(next_line) = lines.peek() {
-                    let trimmed = next_line.trim_end();
 
-                    if trimmed.is_empty() || trimmed.starts_with('#') {
-                        lines.next();
-                        continue; 
-                    }
-
-                    if trimmed.starts_with(' ') || trimmed.starts_with('\t') {
-                        command_line = Some(trimmed.trim());
-                        lines.next(); 
-                        break;
-                    }
-
-                    break;
-                }
-
-                if let Some(command) = command_line {
-                    
-                    let mut modifiers = BTreeSet::new();
-                    let mut key = String::new();
-
-                    let parts = hotkey_line.split('+').map(|s| s.trim().to_lowercase()).collect::<Vec<_>>();
+// A missing file is a fresh start; anything unreadable is an error so the
+// caller can keep the file instead of overwriting it with an empty state.
+pub fn load_from_json_file(path: &str) -> Result<Self, String> {
+    match fs::read_to_string(path) {
+        Ok(json) => Self::from_json(&json).map_err(|e| format!("Could not load {path}: {e}")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+        Err(e) => Err(format!("Could not read {path}: {e}")),
+    }
+}
 
-                    if let Some(k) = parts.last() {
-                        key = k.clone();
-                        for m in &parts[0..parts.len().saturating_sub(1)] {
-                            if !m.is_empty() {
-                                modifiers.insert(m.clone());
-                            }
-                        }
-                    }
+// Parses state written by any earlier version, migrating it step by step.
+pub fn from_json(json: &str) -> Result<Self, String> {
+    let value = Self::migrate_json(json)?;
+    Self::validate_state(&value)?;
+    let mut state: Self = serde_json::from_value(value).map_err(|e| e.to_string())?;
+    state.ensure_mode();
+    Ok(state)
+}
 
-                    hotkeys.push(GuiHotkey {
-                        modifiers,
-                        key,
-                        action: GuiAction {
-                            command: command.to_string(),
-                            active: true,
-                            layer_id: 0,
-                        },
-                    });
-                }
-            }
-        }
+// The state JSON in the current layout, whatever version wrote it.
+pub fn migrate_json(json: &str) -> Result<serde_json::Value, String> {
+    let mut value: serde_json::Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let object = value.as_object().ok_or("expected a JSON object")?;
+    // Files from before versioning have no `version` key.
+    let mut version = match object.get("version") {
+        None => 1,
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or(format!("invalid version {v}"))?,
+    };
+    if version == 0 || version > STATE_VERSION {
+        return Err(format!("state version {version} is not supported (this build reads up to {STATE_VERSION})"));
     }
+    while version < STATE_VERSION {
+        STATE_MIGRATIONS[version as usize - 1](&mut value)?;
+        version += 1;
+        value["version"] = version.into();
+    }
+    Ok(value)
+}
 
-    self.modes.push(AppMode {
-        name: "Default".to_string(),
-        hotkeys,
-    });
-
-    self.selected_mode = 0;
+// JSON Schema of the current state layout, for tools that write the state
+// file themselves.
+pub fn json_schema() -> serde_json::Value {
+    serde_json::to_value(schemars::schema_for!(AppState)).unwrap_or_default()
+}
 
-    Ok(())
+// Checks `state` against `json_schema`, listing every violation with the
+// JSON pointer of the offending field.
+pub fn validate_state(state: &serde_json::Value) -> Result<(), String> {
+    let schema = Self::json_schema();
+    let compiled = jsonschema::JSONSchema::compile(&schema).map_err(|e| format!("invalid state schema: {e}"))?;
+    let problems: Vec<String> = match compiled.validate(state) {
+        Ok(()) => return Ok(()),
+        Err(errors) => errors
+            .map(|e| {
+                let path = e.instance_path.to_string();
+                format!("{}: {e}", if path.is_empty() { "/" } else { &path })
+            })
+            .collect(),
+    };
+    Err(problems.join("\n"))
 }
 
-pub fn load_from_json_file(path: &str) -> Self {
-    use std::fs;
-    if let Ok(json) = fs::read_to_string(path) {
-        serde_json::from_str(&json).unwrap_or_default()
-    } else {
-        Self::default()
-    }
+// Renames an unreadable state file to `<name>.corrupt.<millis>` so it can be
+// recovered by hand, returning the new path.
+pub fn set_aside_state_file(path: &str) -> Result<PathBuf, String> {
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    let aside = PathBuf::from(format!("{path}.corrupt.{millis}"));
+    fs::rename(path, &aside).map_err(|e| format!("Could not move {path} aside: {e}"))?;
+    Ok(aside)
 }
 pub fn new() -> Self {
     Self::default()
 }
 
-    pub fn get_swhkd_config_path(&self) -> Result<PathBuf, Box<dyn std::error::Error>> {
-        let home = std::env::var("HOME").map_err(|_| "Could not determine $HOME")?;
-        Ok(PathBuf::from(home).join(".config/swhkd/swhkdrc"))
-    }
-
-    pub fn load_from_swhkd_config(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let config_path = self.get_swhkd_config_path()?;
-        self.modes.clear();
-
-        let mut hotkeys = Vec::new();
-        if config_path.exists() {
-            let contents = fs::read_to_string(&config_path)?;
-            
-            for line in contents.lines() {
-                let line = line.trim();
-                if line.is_empty() || line.starts_with('#') {
-                    continue;
-                }
-                
-                
-                if let Some((combo, command)) = line.split_once('\n') {
-                    let mut modifiers = BTreeSet::new();
-                    let mut key = String::new();
-                    
-                    let mut combo_parts = combo.split('+').map(|s| s.trim().to_lowercase()).collect::<Vec<_>>();
-                    if let Some(last) = combo_parts.pop() {
-                        key = last;
-                        for part in combo_parts {
-                            modifiers.insert(part);
-                        }
-                    }
-                    
-                    hotkeys.push(GuiHotkey {
-                        modifiers,
-                        key,
-                        action: GuiAction {
-                            command: command.trim().to_string(),
-                            active: true,
-                            layer_id: 0,
-                        },
-                    });
-                }
-            }
-        }
-        
-        self.modes.push(AppMode {
-            name: "Default".to_string(),
-            hotkeys,
-        });
-        self.selected_mode = 0;
-        Ok(())
-    }
-
     pub fn load_from_swhkd_config_at(&mut self, config_path: &str) -> Result<(), Box<dyn std::error::Error>> {
     let path = PathBuf::from(config_path.replace("$HOME", &std::env::var("HOME")?));
     if !path.exists() {
         return Err("File not found".into());
     }
-    self.modes.clear();
 
     let contents = fs::read_to_string(&path)?;
+    self.load_from_swhkd_config_str(&contents);
+    Ok(())
+}
+
+    pub fn load_from_swhkd_config_str(&mut self, contents: &str) {
+    self.modes.clear();
+    self.includes.clear();
+
     let mut lines = contents.lines().map(str::trim_end).peekable();
 
     let mut modes = Vec::new();
     let mut current_mode_name = "Default".to_string();
     let mut hotkeys = Vec::new();
+    let mut description = None;
+    let mut tags = Vec::new();
 
     while let Some(line) = lines.next() {
         let line = line.trim_end();
+        if let Some(text) = line.strip_prefix(DESCRIPTION_COMMENT) {
+            description = Some(text.trim().to_string()).filter(|d| !d.is_empty());
+            continue;
+        }
+        if let Some(text) = line.strip_prefix(TAGS_COMMENT) {
+            tags = parse_tags(text);
+            continue;
+        }
         if line.is_empty() || line.starts_with('#') {
+            description = None;
+            tags.clear();
             continue;
         }
-        if let Some(rest) = line.strip_prefix('@') {
+        if line.starts_with("include ") || line.starts_with("import ") {
+            let mode = Some(current_mode_name.trim().to_string()).filter(|name| !is_default_mode(name));
+            self.includes.push(Include { mode, line: line.trim().to_string() });
+            continue;
+        }
+        // swhkd mode blocks are `mode <name>` ... `endmode`; older GUI
+        // versions wrote a bare `@<name>` header instead.
+        let mode_header = line
+            .strip_prefix("mode ")
+            .or_else(|| line.strip_prefix('@'))
+            .map(|name| name.trim().to_string());
+        if mode_header.is_some() || line == "endmode" {
             if !hotkeys.is_empty() || !modes.is_empty() {
-                modes.push(AppMode {
-                    name: std::mem::take(&mut current_mode_name),
-                    hotkeys: std::mem::take(&mut hotkeys),
-                });
+                modes.push(AppMode::new(std::mem::take(&mut current_mode_name), std::mem::take(&mut hotkeys)));
             }
-            current_mode_name = rest.trim().to_string();
+            current_mode_name = mode_header.unwrap_or_else(|| "Default".to_string());
             continue;
         }
         if !line.starts_with(' ') && !line.starts_with('\t') {
@@ -275,28 +571,40 @@ pub fn new() -> Self {
                         }
                     }
                 }
-                hotkeys.push(GuiHotkey {
+                let (command, env, working_dir) = unwrap_command(command);
+                hotkeys.push(GuiHotkey::new(
                     modifiers,
                     key,
-                    action: GuiAction {
-                        command: command.to_string(),
+                    GuiAction {
+                        command,
                         active: true,
                         layer_id: 0,
+                        description: description.take(),
+                        tags: std::mem::take(&mut tags),
+                        env,
+                        working_dir,
                     },
-                });
+                ));
             }
         }
+        description = None;
+        tags.clear();
     }
     if !hotkeys.is_empty() || modes.is_empty() {
-        modes.push(AppMode {
-            name: current_mode_name,
-            hotkeys,
-        });
+        modes.push(AppMode::new(current_mode_name, hotkeys));
+    }
+
+    // Bindings after an `endmode` land in a second "Default" entry.
+    let mut merged: Vec<AppMode> = Vec::new();
+    for mode in modes {
+        match merged.iter_mut().find(|m| m.name == mode.name) {
+            Some(existing) => existing.hotkeys.extend(mode.hotkeys),
+            None => merged.push(mode),
+        }
     }
 
-    self.modes = modes;
+    self.modes = merged;
     self.selected_mode = 0;
-    Ok(())
 }
 
 
@@ -304,22 +612,7 @@ pub fn new() -> Self {
 
     
     pub fn save_to_custom_path(&self, path: &str) -> Result<(), String> {
-        
-        let mut seen = HashSet::new();
-        for mode in &self.modes {
-            for hk in &mode.hotkeys {
-                if hk.action.active {
-                    let sig = (hk.modifiers.clone(), hk.key.clone());
-                    if !seen.insert(sig) {
-                        return Err(format!(
-                            "Duplicate binding: {} + {}",
-                            hk.modifiers.iter().cloned().collect::<Vec<_>>().join(" + "),
-                            hk.key
-                        ));
-                    }
-                }
-            }
-        }
+        self.check_duplicate_bindings()?;
 
         let config_path = PathBuf::from(path);
 
@@ -327,90 +620,306 @@ pub fn new() -> Self {
             let _ = fs::create_dir_all(parent);
         }
 
-        let mut config_text = String::new();
-        for mode in &self.modes {
-            if mode.name.to_lowercase() != "default" {
-                config_text.push_str(&format!("@{}\n\n", mode.name));
-            }
-            for hk in &mode.hotkeys {
-                if hk.action.active {
-                    let mut mod_vec = hk.modifiers.iter().cloned().collect::<Vec<_>>();
-                    mod_vec.sort();
-                    let combo = if mod_vec.is_empty() {
-                        hk.key.clone()
-                    } else {
-                        format!("{} + {}", mod_vec.join(" + "), hk.key)
-                    };
-                    config_text.push_str(&format!("{}\n    {}\n\n", combo, hk.action.command));
-                }
-            }
-            config_text.push('\n');
+        fs::write(&config_path, self.to_swhkd_config_text()).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    // Copies the current file at `path` to `<name>.bak.<millis>` next to it and
+    // removes all but the newest `keep` copies. Nothing happens when `keep` is
+    // 0 or the file doesn't exist yet.
+    pub fn backup_config(&mut self, path: &Path, keep: usize) -> Result<(), String> {
+        if keep == 0 || !path.exists() {
+            return Ok(());
         }
+        let (Some(dir), Some(name)) = (path.parent(), path.file_name().and_then(|n| n.to_str())) else {
+            return Ok(());
+        };
+        let prefix = format!("{name}.bak.");
+        let millis = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+        let backup = dir.join(format!("{prefix}{millis}"));
+        fs::copy(path, &backup).map_err(|e| format!("Could not back up {}: {e}", path.display()))?;
+        self.last_backup = Some(backup);
 
-        fs::write(&config_path, config_text).map_err(|e| e.to_string())?;
+        let mut backups: Vec<(u128, PathBuf)> = fs::read_dir(dir)
+            .map_err(|e| e.to_string())?
+            .flatten()
+            .filter_map(|entry| {
+                let stamp = entry.file_name().to_str()?.strip_prefix(&prefix)?.parse().ok()?;
+                Some((stamp, entry.path()))
+            })
+            .collect();
+        backups.sort();
+        let excess = backups.len().saturating_sub(keep);
+        for (_, old) in backups.into_iter().take(excess) {
+            let _ = fs::remove_file(old);
+        }
         Ok(())
     }
 
-    
-    pub fn save_to_swhkd_config(&self) -> Result<(), String> {
-        let config_path = self.get_swhkd_config_path().map_err(|e| e.to_string())?;
-        
-        
+    // Active bindings in `mode` that share a combo with another active binding
+    // in the same mode, using the same rule as `check_duplicate_bindings`.
+    pub fn conflicting_signatures(&self, mode: usize) -> HashSet<(BTreeSet<String>, String)> {
         let mut seen = HashSet::new();
+        let mut conflicts = HashSet::new();
+        for hk in self.modes.get(mode).into_iter().flat_map(|m| &m.hotkeys) {
+            if hk.action.active && !seen.insert(hk.signature()) {
+                conflicts.insert(hk.signature());
+            }
+        }
+        conflicts
+    }
+
+    // swhkd only looks at one mode at a time, so the same combo may be bound
+    // once per mode.
+    pub fn check_duplicate_bindings(&self) -> Result<(), String> {
         for mode in &self.modes {
+            let mut seen = HashSet::new();
             for hk in &mode.hotkeys {
-                if hk.action.active {
-                    let sig = (hk.modifiers.clone(), hk.key.clone());
-                    if !seen.insert(sig) {
-                        return Err(format!(
-                            "Duplicate binding: {} + {}",
-                            hk.modifiers.iter().cloned().collect::<Vec<_>>().join(" + "),
-                            hk.key
-                        ));
-                    }
+                if hk.action.active && !seen.insert(hk.signature()) {
+                    return Err(format!("Duplicate binding in {}: {}", mode.name, hk.combo_string()));
                 }
             }
         }
+        Ok(())
+    }
 
-        
-        if let Some(parent) = config_path.parent() {
-            let _ = fs::create_dir_all(parent);
+    // Moves (or copies) the given rows of mode `from` to the end of mode `to`.
+    // Active rows whose combo is already bound in the target mode are left
+    // where they are; their combos are returned so the caller can report them.
+    pub fn transfer_hotkeys(&mut self, from: usize, rows: &[usize], to: usize, copy: bool) -> Result<Vec<String>, String> {
+        if from >= self.modes.len() || to >= self.modes.len() {
+            return Err("No such mode".to_string());
+        }
+        if from == to {
+            return Err(format!("The hotkeys are already in {}", self.modes[to].name));
+        }
+        let mut taken: HashSet<_> = self.modes[to]
+            .hotkeys
+            .iter()
+            .filter(|hk| hk.action.active)
+            .map(GuiHotkey::signature)
+            .collect();
+        let rows: BTreeSet<usize> = rows.iter().copied().filter(|&i| i < self.modes[from].hotkeys.len()).collect();
+
+        let mut skipped = Vec::new();
+        let mut transferred = Vec::new();
+        for &i in &rows {
+            let hk = &self.modes[from].hotkeys[i];
+            if hk.action.active && !taken.insert(hk.signature()) {
+                skipped.push(hk.combo_string());
+            } else {
+                transferred.push(i);
+            }
+        }
+        for &i in &transferred {
+            let hk = &self.modes[from].hotkeys[i];
+            let hk = if copy { hk.fresh_copy() } else { hk.clone() };
+            self.modes[to].hotkeys.push(hk);
+        }
+        if !copy {
+            for &i in transferred.iter().rev() {
+                self.modes[from].hotkeys.remove(i);
+            }
+        }
+        Ok(skipped)
+    }
+
+    // The GUI always needs a mode to show: an empty list gets a fresh
+    // "Default" mode and `selected_mode` is kept in range.
+    pub fn ensure_mode(&mut self) {
+        if self.modes.is_empty() {
+            self.modes = Self::default().modes;
+        }
+        self.selected_mode = self.selected_mode.min(self.modes.len() - 1);
+    }
+
+    // Removes mode `idx`, keeping the same mode selected when it is another
+    // one. Deleting the only mode leaves an empty "Default" mode behind.
+    pub fn delete_mode(&mut self, idx: usize) -> Option<AppMode> {
+        if idx >= self.modes.len() {
+            return None;
+        }
+        let removed = self.modes.remove(idx);
+        if self.selected_mode > idx {
+            self.selected_mode -= 1;
+        }
+        self.ensure_mode();
+        Some(removed)
+    }
+
+    // `base`, or `base_2`, `base_3`, ... for the first name no mode uses yet.
+    // swhkd mode names are a single word, so spaces in `base` become
+    // underscores. Names are compared the way swhkdrc sees them: any case.
+    pub fn unique_mode_name(&self, base: &str) -> String {
+        let base = base.split_whitespace().collect::<Vec<_>>().join("_");
+        let taken = |name: &str| self.modes.iter().any(|m| m.name.trim().eq_ignore_ascii_case(name));
+        if !taken(&base) {
+            return base;
+        }
+        (2..).map(|n| format!("{base}_{n}")).find(|name| !taken(name)).unwrap()
+    }
+
+    // Appends a mode under a unique name based on `name` and returns its index.
+    pub fn add_mode(&mut self, name: &str, hotkeys: Vec<GuiHotkey>) -> usize {
+        let name = self.unique_mode_name(name);
+        self.modes.push(AppMode::new(name, hotkeys));
+        self.modes.len() - 1
+    }
+
+    // Inserts a copy of mode `idx` right after it and returns the copy's index.
+    pub fn duplicate_mode(&mut self, idx: usize) -> Option<usize> {
+        let mode = self.modes.get(idx)?;
+        let copy = AppMode::new(
+            self.unique_mode_name(&format!("{}_copy", mode.name.trim())),
+            mode.hotkeys.iter().map(GuiHotkey::fresh_copy).collect(),
+        );
+        self.modes.insert(idx + 1, copy);
+        Some(idx + 1)
+    }
+
+    // The order of modes and rows is the order they are written to swhkdrc.
+    // Moves row `from` to where row `to` is now.
+    pub fn reorder_hotkey(&mut self, mode: usize, from: usize, to: usize) {
+        if let Some(mode) = self.modes.get_mut(mode) {
+            move_item(&mut mode.hotkeys, from, to);
+        }
+    }
+
+    // Moves a mode, keeping the same mode selected.
+    pub fn reorder_mode(&mut self, from: usize, to: usize) {
+        if !move_item(&mut self.modes, from, to) {
+            return;
         }
+        let selected = self.selected_mode;
+        self.selected_mode = if selected == from {
+            to
+        } else if from < selected && selected <= to {
+            selected - 1
+        } else if to <= selected && selected < from {
+            selected + 1
+        } else {
+            selected
+        };
+    }
+
+    // Bulk edits on rows of one mode; rows that don't exist are ignored.
+    fn rows_mut<'a>(&'a mut self, mode: usize, rows: &'a [usize]) -> impl Iterator<Item = &'a mut GuiHotkey> + 'a {
+        self.modes
+            .get_mut(mode)
+            .into_iter()
+            .flat_map(|m| m.hotkeys.iter_mut().enumerate())
+            .filter(move |(i, _)| rows.contains(i))
+            .map(|(_, hk)| hk)
+    }
+
+    pub fn set_active(&mut self, mode: usize, rows: &[usize], active: bool) {
+        for hk in self.rows_mut(mode, rows) {
+            hk.action.active = active;
+        }
+    }
+
+    pub fn add_tags(&mut self, mode: usize, rows: &[usize], tags: &[String]) {
+        for hk in self.rows_mut(mode, rows) {
+            for tag in tags {
+                if !hk.action.tags.contains(tag) {
+                    hk.action.tags.push(tag.clone());
+                }
+            }
+        }
+    }
+
+    pub fn remove_tag(&mut self, mode: usize, rows: &[usize], tag: &str) {
+        for hk in self.rows_mut(mode, rows) {
+            hk.action.tags.retain(|t| t != tag);
+        }
+    }
 
-        
+    pub fn delete_hotkeys(&mut self, mode: usize, rows: &[usize]) {
+        if let Some(mode) = self.modes.get_mut(mode) {
+            let mut i = 0;
+            mode.hotkeys.retain(|_| {
+                i += 1;
+                !rows.contains(&(i - 1))
+            });
+        }
+    }
+
+    // Edits missing from the state file (`saved_modes`) or from swhkdrc.
+    // `config_on_disk` is the config as last read or written; it is compared
+    // by its bindings, so formatting written by hand doesn't count.
+    pub fn has_unsaved_changes(&self, saved_modes: &[AppMode], config_on_disk: Option<&str>) -> bool {
+        if self.modes != saved_modes {
+            return true;
+        }
+        let text = self.to_swhkd_config_text();
+        if config_on_disk == Some(text.as_str()) {
+            return false;
+        }
+        let mut on_disk = AppState::default();
+        on_disk.load_from_swhkd_config_str(config_on_disk.unwrap_or_default());
+        on_disk.to_swhkd_config_text() != text
+    }
+
+    // Descriptions and tags are written as structured comments directly above
+    // the binding so they survive a round trip through swhkdrc.
+    pub fn to_swhkd_config_text(&self) -> String {
         let mut config_text = String::new();
+        let includes_of = |mode: Option<&str>| {
+            let lines: String = self
+                .includes
+                .iter()
+                .filter(|include| include.mode.as_deref() == mode)
+                .map(|include| format!("{}\n", include.line))
+                .collect();
+            if lines.is_empty() { lines } else { lines + "\n" }
+        };
+        config_text.push_str(&includes_of(None));
         for mode in &self.modes {
-            if mode.name.to_lowercase() != "default" {
-                config_text.push_str(&format!("@{}\n\n", mode.name));
+            let is_default = is_default_mode(&mode.name);
+            if !is_default {
+                config_text.push_str(&format!("mode {}\n\n", mode.name.trim()));
+                config_text.push_str(&includes_of(Some(mode.name.trim())));
             }
             for hk in &mode.hotkeys {
                 if hk.action.active {
-                    let mut mod_vec = hk.modifiers.iter().cloned().collect::<Vec<_>>();
-                    mod_vec.sort();
-                    let combo = if mod_vec.is_empty() {
-                        hk.key.clone()
-                    } else {
-                        format!("{} + {}", mod_vec.join(" + "), hk.key)
-                    };
-                    config_text.push_str(&format!("{}\n    {}\n\n", combo, hk.action.command));
+                    if let Some(description) = &hk.action.description {
+                        config_text.push_str(&format!("{}{}\n", DESCRIPTION_COMMENT, description.replace('\n', " ")));
+                    }
+                    if !hk.action.tags.is_empty() {
+                        config_text.push_str(&format!("{}{}\n", TAGS_COMMENT, hk.action.tags.join(", ")));
+                    }
+                    config_text.push_str(&format!("{}\n    {}\n\n", hk.combo_string(), hk.action.shell_command()));
                 }
             }
+            if !is_default {
+                config_text.push_str("endmode\n\n");
+            }
         }
-
-        
-        fs::write(&config_path, config_text).map_err(|e| e.to_string())?;
-        
-        
-        let _ = self.reload_swhkd();
-        
-        Ok(())
+        // Includes of a mode that is gone keep a block of their own, so
+        // their bindings still belong to that mode.
+        let mut orphaned: Vec<&str> = Vec::new();
+        for mode in self.includes.iter().filter_map(|include| include.mode.as_deref()) {
+            if !orphaned.contains(&mode) && !self.modes.iter().any(|m| !is_default_mode(&m.name) && m.name.trim() == mode) {
+                orphaned.push(mode);
+            }
+        }
+        for mode in orphaned {
+            config_text.push_str(&format!("mode {mode}\n\n{}endmode\n\n", includes_of(Some(mode))));
+        }
+        config_text
     }
+}
 
-    fn reload_swhkd(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let _ = Command::new("pkill").arg("-USR1").arg("swhkd").output();
-        let _ = Command::new("systemctl").args(["--user", "restart", "swhkd"]).output();
-        Ok(())
+fn move_item<T>(items: &mut Vec<T>, from: usize, to: usize) -> bool {
+    if from == to || from >= items.len() || to >= items.len() {
+        return false;
     }
+    let item = items.remove(from);
+    items.insert(to, item);
+    true
 }
 
 fn modifier_to_string(m: &Modifier) -> String {
@@ -485,6 +994,32 @@ fn key_to_string(key: &Key) -> String {
         EvdevKey::KEY_COMMA => "comma",
         EvdevKey::KEY_DOT => "dot",
         EvdevKey::KEY_SLASH => "slash",
+        EvdevKey::KEY_GRAVE => "grave",
+        EvdevKey::KEY_BACKSPACE => "backspace",
+        EvdevKey::KEY_CAPSLOCK => "capslock",
+        EvdevKey::KEY_SYSRQ => "print",
+        EvdevKey::KEY_INSERT => "insert",
+        EvdevKey::KEY_DELETE => "delete",
+        EvdevKey::KEY_HOME => "home",
+        EvdevKey::KEY_END => "end",
+        EvdevKey::KEY_PAGEUP => "pageup",
+        EvdevKey::KEY_PAGEDOWN => "pagedown",
+        EvdevKey::KEY_LEFT => "left",
+        EvdevKey::KEY_UP => "up",
+        EvdevKey::KEY_DOWN => "down",
+        EvdevKey::KEY_RIGHT => "right",
+        EvdevKey::KEY_F1 => "f1",
+        EvdevKey::KEY_F2 => "f2",
+        EvdevKey::KEY_F3 => "f3",
+        EvdevKey::KEY_F4 => "f4",
+        EvdevKey::KEY_F5 => "f5",
+        EvdevKey::KEY_F6 => "f6",
+        EvdevKey::KEY_F7 => "f7",
+        EvdevKey::KEY_F8 => "f8",
+        EvdevKey::KEY_F9 => "f9",
+        EvdevKey::KEY_F10 => "f10",
+        EvdevKey::KEY_F11 => "f11",
+        EvdevKey::KEY_F12 => "f12",
         _ => {
             
             return format!("{:?}", key.key);
@@ -493,7 +1028,8 @@ fn key_to_string(key: &Key) -> String {
     .to_string()
 }
 
-fn string_to_evdev_key(s: &str) -> Result<EvdevKey, String> {
+// Key names the GUI writes, as swhkd spells them.
+pub fn string_to_evdev_key(s: &str) -> Result<EvdevKey, String> {
     match s.to_ascii_lowercase().as_str() {
         "a" => Ok(EvdevKey::KEY_A),
         "b" => Ok(EvdevKey::KEY_B),
@@ -545,6 +1081,32 @@ fn string_to_evdev_key(s: &str) -> Result<EvdevKey, String> {
         "comma" => Ok(EvdevKey::KEY_COMMA),
         "dot" => Ok(EvdevKey::KEY_DOT),
         "slash" => Ok(EvdevKey::KEY_SLASH),
+        "grave" => Ok(EvdevKey::KEY_GRAVE),
+        "backspace" => Ok(EvdevKey::KEY_BACKSPACE),
+        "capslock" => Ok(EvdevKey::KEY_CAPSLOCK),
+        "print" => Ok(EvdevKey::KEY_SYSRQ),
+        "insert" => Ok(EvdevKey::KEY_INSERT),
+        "delete" => Ok(EvdevKey::KEY_DELETE),
+        "home" => Ok(EvdevKey::KEY_HOME),
+        "end" => Ok(EvdevKey::KEY_END),
+        "pageup" => Ok(EvdevKey::KEY_PAGEUP),
+        "pagedown" => Ok(EvdevKey::KEY_PAGEDOWN),
+        "left" => Ok(EvdevKey::KEY_LEFT),
+        "up" => Ok(EvdevKey::KEY_UP),
+        "down" => Ok(EvdevKey::KEY_DOWN),
+        "right" => Ok(EvdevKey::KEY_RIGHT),
+        "f1" => Ok(EvdevKey::KEY_F1),
+        "f2" => Ok(EvdevKey::KEY_F2),
+        "f3" => Ok(EvdevKey::KEY_F3),
+        "f4" => Ok(EvdevKey::KEY_F4),
+        "f5" => Ok(EvdevKey::KEY_F5),
+        "f6" => Ok(EvdevKey::KEY_F6),
+        "f7" => Ok(EvdevKey::KEY_F7),
+        "f8" => Ok(EvdevKey::KEY_F8),
+        "f9" => Ok(EvdevKey::KEY_F9),
+        "f10" => Ok(EvdevKey::KEY_F10),
+        "f11" => Ok(EvdevKey::KEY_F11),
+        "f12" => Ok(EvdevKey::KEY_F12),
         _ => Err(format!("Unsupported key: {}", s)),
     }
 }
\ No newline at end of file
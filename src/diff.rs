@@ -0,0 +1,102 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+// Line diff from the longest common subsequence. Configs are a few hundred
+// lines at most, so the quadratic table is fine.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // lcs[i][j]: common lines between old[i..] and new[j..].
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::new();
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            lines.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            lines.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    lines.extend(old[i..].iter().map(|l| DiffLine::Removed(l.to_string())));
+    lines.extend(new[j..].iter().map(|l| DiffLine::Added(l.to_string())));
+    lines
+}
+
+pub fn has_changes(lines: &[DiffLine]) -> bool {
+    lines.iter().any(|l| !matches!(l, DiffLine::Same(_)))
+}
+
+// Changed lines with `context` unchanged lines around them; `None` marks
+// skipped unchanged lines.
+pub fn unified(lines: &[DiffLine], context: usize) -> Vec<Option<&DiffLine>> {
+    let changed: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| !matches!(l, DiffLine::Same(_)))
+        .map(|(i, _)| i)
+        .collect();
+    let near_change = |i: usize| changed.iter().any(|&c| c.abs_diff(i) <= context);
+
+    let mut rows = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if near_change(i) {
+            rows.push(Some(line));
+        } else if rows.last().is_some_and(|row| row.is_some()) || (rows.is_empty() && i == 0) {
+            rows.push(None);
+        }
+    }
+    rows
+}
+
+// Old and new text next to each other. Runs of removed lines are paired
+// with the added lines that follow them.
+pub fn side_by_side(lines: &[DiffLine]) -> Vec<(Option<&DiffLine>, Option<&DiffLine>)> {
+    let mut rows = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        match &lines[i] {
+            DiffLine::Same(_) => {
+                rows.push((Some(&lines[i]), Some(&lines[i])));
+                i += 1;
+            }
+            _ => {
+                let removed_end = lines[i..]
+                    .iter()
+                    .position(|l| !matches!(l, DiffLine::Removed(_)))
+                    .map_or(lines.len(), |p| i + p);
+                let added_end = lines[removed_end..]
+                    .iter()
+                    .position(|l| !matches!(l, DiffLine::Added(_)))
+                    .map_or(lines.len(), |p| removed_end + p);
+                let removed = &lines[i..removed_end];
+                let added = &lines[removed_end..added_end];
+                for k in 0..removed.len().max(added.len()) {
+                    rows.push((removed.get(k), added.get(k)));
+                }
+                i = added_end;
+            }
+        }
+    }
+    rows
+}
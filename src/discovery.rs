@@ -0,0 +1,127 @@
+use crate::settings::{AppSettings, SettingsOverrides};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+// swhkd's own default when started without `-c`.
+pub const SYSTEM_CONFIG: &str = "/etc/swhkd/swhkdrc";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigSource {
+    CommandLine,
+    Settings,
+    XdgConfigHome,
+    HomeConfig,
+    System,
+    // Nothing exists yet; the file is created on the first save.
+    NewFile,
+}
+
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ConfigSource::CommandLine => "from --config",
+            ConfigSource::Settings => "from settings",
+            ConfigSource::XdgConfigHome => "found in $XDG_CONFIG_HOME",
+            ConfigSource::HomeConfig => "found in ~/.config",
+            ConfigSource::System => "system-wide",
+            ConfigSource::NewFile => "not created yet",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigLocation {
+    pub path: PathBuf,
+    pub source: ConfigSource,
+}
+
+// Places swhkdrc is looked for, in order.
+pub fn candidates() -> Vec<(PathBuf, ConfigSource)> {
+    let mut candidates = Vec::new();
+    if let Some(xdg) = std::env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+        candidates.push((PathBuf::from(xdg).join("swhkd/swhkdrc"), ConfigSource::XdgConfigHome));
+    }
+    if let Some(home) = dirs::home_dir() {
+        let path = home.join(".config/swhkd/swhkdrc");
+        if !candidates.iter().any(|(p, _)| *p == path) {
+            candidates.push((path, ConfigSource::HomeConfig));
+        }
+    }
+    candidates.push((PathBuf::from(SYSTEM_CONFIG), ConfigSource::System));
+    candidates
+}
+
+// The first candidate that exists, or the first user-level one to create.
+pub fn discover() -> ConfigLocation {
+    let candidates = candidates();
+    if let Some((path, source)) = candidates.iter().find(|(path, _)| path.is_file()) {
+        return ConfigLocation { path: path.clone(), source: *source };
+    }
+    ConfigLocation {
+        path: candidates[0].0.clone(),
+        source: ConfigSource::NewFile,
+    }
+}
+
+// An explicit path wins over discovery, the command line over settings.
+pub fn locate(settings: &AppSettings, overrides: &SettingsOverrides) -> ConfigLocation {
+    if let Some(path) = &overrides.config_path {
+        ConfigLocation { path: path.clone(), source: ConfigSource::CommandLine }
+    } else if let Some(path) = &settings.config_path {
+        ConfigLocation { path: path.clone(), source: ConfigSource::Settings }
+    } else {
+        discover()
+    }
+}
+
+// Whether writing `path` needs root: the file can't be opened for writing,
+// or, for a new file, nothing can be created in its nearest existing
+// directory. Both are tried rather than judged from the owner, so
+// group-writable and world-writable directories count as writable.
+pub fn needs_privilege(path: &Path) -> bool {
+    let denied = |result: std::io::Result<fs::File>| {
+        matches!(result, Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied)
+    };
+    if path.exists() {
+        return denied(fs::OpenOptions::new().append(true).open(path));
+    }
+    let Some(dir) = path.ancestors().skip(1).find(|dir| dir.is_dir()) else {
+        return false;
+    };
+    let probe = dir.join(format!(".swhkd-gui-write-test-{}", std::process::id()));
+    let created = fs::OpenOptions::new().write(true).create_new(true).open(&probe);
+    if created.is_ok() {
+        let _ = fs::remove_file(&probe);
+    }
+    denied(created)
+}
+
+// Writes `text` to `path` as root through pkexec, creating the directory if
+// needed. The text goes over stdin so nothing is left in a temporary file.
+pub fn write_privileged(path: &Path, text: &str) -> Result<(), String> {
+    let dir = path.parent().unwrap_or(Path::new("/"));
+    let mut child = Command::new("pkexec")
+        .args(["sh", "-c", "mkdir -p \"$1\" && cat > \"$2\"", "sh"])
+        .arg(dir)
+        .arg(path)
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Could not run pkexec: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes()).map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "pkexec could not write {} ({}): {}",
+            path.display(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
@@ -0,0 +1,271 @@
+use crate::data_model::{GuiAction, GuiHotkey};
+use std::collections::BTreeSet;
+use std::fs;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortcutSource {
+    // `dconf dump /org/gnome/settings-daemon/plugins/media-keys/custom-keybindings/`
+    Gnome,
+    // ~/.config/kglobalshortcutsrc
+    KdeGlobal,
+    // khotkeys `.khotkeys` export
+    KHotkeys,
+}
+
+pub fn import_shortcuts_file(path: &str) -> Result<Vec<GuiHotkey>, String> {
+    let contents = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    import_shortcuts(&contents)
+}
+
+pub fn import_shortcuts(contents: &str) -> Result<Vec<GuiHotkey>, String> {
+    let hotkeys = match detect_source(contents) {
+        ShortcutSource::Gnome => import_gnome_dconf(contents),
+        ShortcutSource::KdeGlobal => import_kde_global(contents),
+        ShortcutSource::KHotkeys => import_khotkeys(contents),
+    };
+    if hotkeys.is_empty() {
+        return Err("No custom shortcuts with a command were found in this file".to_string());
+    }
+    Ok(hotkeys)
+}
+
+pub fn detect_source(contents: &str) -> ShortcutSource {
+    if contents.lines().any(|l| l.trim_start().starts_with("binding=")) {
+        ShortcutSource::Gnome
+    } else if contents.contains("Triggers0]") || contents.contains("SIMPLE_ACTION_DATA") {
+        ShortcutSource::KHotkeys
+    } else {
+        ShortcutSource::KdeGlobal
+    }
+}
+
+pub fn import_gnome_dconf(contents: &str) -> Vec<GuiHotkey> {
+    let mut hotkeys = Vec::new();
+    for (_, entries) in parse_ini(contents) {
+        let binding = ini_value(&entries, "binding").map(unquote_gvariant);
+        let command = ini_value(&entries, "command").map(unquote_gvariant);
+        let name = ini_value(&entries, "name").map(unquote_gvariant);
+        if let (Some(binding), Some(command)) = (binding, command) {
+            if command.trim().is_empty() {
+                continue;
+            }
+            if let Some((modifiers, key)) = parse_accelerator(&binding) {
+                hotkeys.push(imported_hotkey(modifiers, key, command, name, true));
+            }
+        }
+    }
+    hotkeys
+}
+
+pub fn import_kde_global(contents: &str) -> Vec<GuiHotkey> {
+    let mut hotkeys = Vec::new();
+    for (section, entries) in parse_ini(contents) {
+        // Only application launchers carry something we can run; KWin and
+        // plasmashell actions are internal and have no shell equivalent.
+        if !section.ends_with(".desktop") {
+            continue;
+        }
+        let desktop_id = section
+            .trim_start_matches("services][")
+            .trim_end_matches(".desktop");
+        if let Some(value) = ini_value(&entries, "_launch") {
+            // `<shortcuts>,<default shortcuts>,<friendly name>`
+            let mut fields = value.splitn(3, ',');
+            // KConfig writes the tab between alternative shortcuts as `\t`.
+            let shortcuts = fields.next().unwrap_or_default().replace("\\t", "\t");
+            let name = fields.nth(1).map(str::to_string).or_else(|| ini_value(&entries, "_k_friendly_name"));
+            for shortcut in shortcuts.split('\t') {
+                if let Some((modifiers, key)) = parse_kde_shortcut(shortcut) {
+                    let command = format!("gtk-launch {}", desktop_id);
+                    hotkeys.push(imported_hotkey(modifiers, key, command, name.clone(), true));
+                }
+            }
+        }
+    }
+    hotkeys
+}
+
+pub fn import_khotkeys(contents: &str) -> Vec<GuiHotkey> {
+    let sections = parse_ini(contents);
+    let find = |name: &str| {
+        sections
+            .iter()
+            .find(|(section, _)| section == name)
+            .map(|(_, entries)| entries)
+    };
+
+    let mut hotkeys = Vec::new();
+    for (section, entries) in &sections {
+        if ini_value(entries, "Type").as_deref() != Some("SIMPLE_ACTION_DATA") {
+            continue;
+        }
+        let active = ini_value(entries, "Enabled").as_deref() != Some("false");
+        let name = ini_value(entries, "Comment").or_else(|| ini_value(entries, "Name"));
+        let shortcut = find(&format!("{}Triggers0", section)).and_then(|t| ini_value(t, "Key"));
+        let command = find(&format!("{}Actions0", section)).and_then(|a| ini_value(a, "CommandURL"));
+        if let (Some(shortcut), Some(command)) = (shortcut, command) {
+            if let Some((modifiers, key)) = parse_kde_shortcut(&shortcut) {
+                hotkeys.push(imported_hotkey(modifiers, key, command, name, active));
+            }
+        }
+    }
+    hotkeys
+}
+
+// GTK accelerator syntax, e.g. `<Super><Shift>Return` or `<Primary>t`.
+pub fn parse_accelerator(accel: &str) -> Option<(BTreeSet<String>, String)> {
+    let mut rest = accel.trim();
+    if rest.is_empty() || rest.eq_ignore_ascii_case("disabled") {
+        return None;
+    }
+
+    let mut modifiers = BTreeSet::new();
+    while let Some(stripped) = rest.strip_prefix('<') {
+        let end = stripped.find('>')?;
+        modifiers.insert(translate_modifier(&stripped[..end])?);
+        rest = &stripped[end + 1..];
+    }
+
+    if rest.is_empty() {
+        return None;
+    }
+    Some((modifiers, translate_key(rest)))
+}
+
+// Qt key sequence syntax, e.g. `Meta+Shift+Return` or `Ctrl+Alt+T`.
+pub fn parse_kde_shortcut(shortcut: &str) -> Option<(BTreeSet<String>, String)> {
+    let shortcut = shortcut.trim();
+    if shortcut.is_empty() || shortcut.eq_ignore_ascii_case("none") {
+        return None;
+    }
+
+    let mut parts: Vec<&str> = shortcut.split('+').collect();
+    // A literal plus key shows up as a trailing "++".
+    let key = if shortcut.ends_with("++") {
+        parts.truncate(parts.len() - 2);
+        "+".to_string()
+    } else {
+        parts.pop()?.to_string()
+    };
+
+    let mut modifiers = BTreeSet::new();
+    for part in parts {
+        modifiers.insert(translate_modifier(part)?);
+    }
+    if key.is_empty() {
+        return None;
+    }
+    Some((modifiers, translate_key(&key)))
+}
+
+fn translate_modifier(name: &str) -> Option<String> {
+    match name.trim().to_ascii_lowercase().as_str() {
+        "primary" | "control" | "ctrl" => Some("ctrl".to_string()),
+        "shift" => Some("shift".to_string()),
+        "alt" | "mod1" => Some("alt".to_string()),
+        "super" | "meta" | "mod4" | "hyper" => Some("super".to_string()),
+        _ => None,
+    }
+}
+
+fn translate_key(name: &str) -> String {
+    let lower = name.trim().to_ascii_lowercase();
+    match lower.as_str() {
+        "return" | "enter" | "kp_enter" => "enter",
+        "esc" | "escape" => "escape",
+        "del" => "delete",
+        "pgup" | "page_up" | "prior" => "pageup",
+        "pgdown" | "page_down" | "next" => "pagedown",
+        "caps_lock" => "capslock",
+        "sysrq" => "print",
+        "period" | "." => "dot",
+        "-" => "minus",
+        "=" => "equal",
+        "+" => "plus",
+        "," => "comma",
+        "/" => "slash",
+        "\\" => "backslash",
+        ";" => "semicolon",
+        "'" => "apostrophe",
+        "[" => "bracketleft",
+        "]" => "bracketright",
+        "`" => "grave",
+        "volume up" => "xf86audioraisevolume",
+        "volume down" => "xf86audiolowervolume",
+        "volume mute" => "xf86audiomute",
+        "media play" => "xf86audioplay",
+        "media next" => "xf86audionext",
+        "media previous" => "xf86audioprev",
+        _ => return lower,
+    }
+    .to_string()
+}
+
+fn imported_hotkey(
+    modifiers: BTreeSet<String>,
+    key: String,
+    command: String,
+    description: Option<String>,
+    active: bool,
+) -> GuiHotkey {
+    GuiHotkey::new(
+        modifiers,
+        key,
+        GuiAction {
+            command,
+            active,
+            layer_id: 0,
+            description: description.filter(|d| !d.trim().is_empty()),
+            tags: Vec::new(),
+            env: Vec::new(),
+            working_dir: None,
+        },
+    )
+}
+
+fn parse_ini(contents: &str) -> Vec<(String, Vec<(String, String)>)> {
+    let mut sections: Vec<(String, Vec<(String, String)>)> = Vec::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if line.starts_with('[') && line.ends_with(']') {
+            sections.push((line[1..line.len() - 1].to_string(), Vec::new()));
+            continue;
+        }
+        if let (Some((key, value)), Some((_, entries))) = (line.split_once('='), sections.last_mut()) {
+            entries.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    sections
+}
+
+fn ini_value(entries: &[(String, String)], key: &str) -> Option<String> {
+    entries.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone())
+}
+
+// dconf prints strings as GVariant text: single quoted with backslash escapes.
+fn unquote_gvariant(value: String) -> String {
+    let trimmed = value.trim();
+    let inner = trimmed
+        .strip_prefix('\'')
+        .and_then(|s| s.strip_suffix('\''))
+        .or_else(|| trimmed.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+    let Some(inner) = inner else {
+        return trimmed.to_string();
+    };
+
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(next) = chars.next() {
+                out.push(next);
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
@@ -1,16 +1,38 @@
 use iced::widget::{
-    button, checkbox, column, container, row, scrollable, text, text_input, Space, image, image::Image,
+    button, checkbox, column, container, pick_list, row, scrollable, text, text_input, Space, image, image::Image,
 };
 use iced::{Alignment, Color, Element, Length, BorderRadius, Theme};
 use iced::font::Weight;
-use crate::data_model::AppState;
+use crate::config_import::{ConflictPolicy, ImportOutcome, ImportPlan, ImportTarget};
+use crate::daemon::{ConfigState, DaemonStatus, ReloadReport};
+use crate::diff::{self, DiffLine};
+use crate::discovery::{ConfigLocation, ConfigSource};
+use crate::data_model::{AppMode, AppState, GuiAction, GuiHotkey};
+use crate::keyboard_map::{self, KeyboardLayout};
+use crate::preflight;
+use crate::row_view::{RowFilter, RowView, SortColumn};
+use crate::runner::{RunOutput, RunStatus};
+use crate::settings::{self, AppSettings, ReloadMethod};
+use crate::snippets::Snippet;
+use crate::templates::{ModeTemplate, TEMPLATES};
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 
 const BACKGROUND: Color = Color::WHITE;
 const CARD: Color = Color::from_rgb(0.95, 0.96, 0.97);
 const CARD_BORDER: Color = Color::from_rgb(0.87, 0.89, 0.91);
 const ACCENT: Color = Color::from_rgb(0.40, 0.45, 0.53);
 const ERROR: Color = Color::from_rgb(0.86, 0.21, 0.21);
+const SUCCESS: Color = Color::from_rgb(0.18, 0.55, 0.30);
 const TEXT_PRIMARY: Color = Color::from_rgb(0.11, 0.11, 0.11);
+const KEY_FREE: Color = Color::from_rgb(0.89, 0.96, 0.90);
+const KEY_USED: Color = Color::from_rgb(0.99, 0.92, 0.76);
+const KEY_CONFLICT: Color = Color::from_rgb(0.98, 0.80, 0.80);
+const MODIFIER_ON: Color = Color::from_rgb(0.78, 0.83, 0.92);
+const KEY_UNIT: f32 = 44.0;
+const KEY_SPACING: u16 = 4;
 
 pub struct CardContainer;
 impl iced::widget::container::StyleSheet for CardContainer {
@@ -25,6 +47,19 @@ impl iced::widget::container::StyleSheet for CardContainer {
         }
     }
 }
+pub struct SwatchContainer(pub Color);
+impl iced::widget::container::StyleSheet for SwatchContainer {
+    type Style = Theme;
+    fn appearance(&self, _: &Self::Style) -> iced::widget::container::Appearance {
+        iced::widget::container::Appearance {
+            background: Some(self.0.into()),
+            border_radius: BorderRadius::from(4.0),
+            border_width: 1.0,
+            border_color: CARD_BORDER,
+            ..Default::default()
+        }
+    }
+}
 pub struct CardButton;
 impl button::StyleSheet for CardButton {
     type Style = Theme;
@@ -61,6 +96,25 @@ impl button::StyleSheet for DangerButton {
         a
     }
 }
+pub struct KeyCapButton(pub Color);
+impl button::StyleSheet for KeyCapButton {
+    type Style = Theme;
+    fn active(&self, _: &Self::Style) -> button::Appearance {
+        button::Appearance {
+            background: Some(self.0.into()),
+            border_radius: 6.0.into(),
+            border_width: 1.0,
+            border_color: CARD_BORDER,
+            text_color: TEXT_PRIMARY,
+            ..Default::default()
+        }
+    }
+    fn hovered(&self, _: &Self::Style) -> button::Appearance {
+        let mut a = self.active(&Theme::default());
+        a.border_color = ACCENT;
+        a
+    }
+}
 pub struct PastelTextInput;
 impl text_input::StyleSheet for PastelTextInput {
     type Style = Theme;
@@ -93,31 +147,1236 @@ impl text_input::StyleSheet for PastelTextInput {
     }
 }
 
+const ALL_TAGS: &str = "All tags";
+
+// What to do with the config once it passed the preflight check.
+#[derive(Debug, Clone)]
+pub enum AfterCheck {
+    // Show the diff; writing waits for its confirmation.
+    Preview,
+    SaveAndQuit,
+    SaveAs(String),
+}
+
+// What Save & Apply is about to write, shown for confirmation first.
+#[derive(Debug, Clone)]
+pub struct SavePreview {
+    pub path: PathBuf,
+    pub text: String,
+    pub lines: Vec<DiffLine>,
+}
+
+// A config file being imported; nothing changes until it is confirmed.
+#[derive(Debug, Clone)]
+pub struct ImportWizard {
+    pub path: String,
+    pub plan: ImportPlan,
+}
+
+// A test run of one hotkey's command; `output` is filled in once it ends.
+#[derive(Debug, Clone)]
+pub struct RunPanel {
+    pub command: String,
+    pub cancel: Arc<AtomicBool>,
+    pub output: Option<RunOutput>,
+}
+
+// The snippet library, opened from one hotkey row. `values` holds what was
+// typed for the placeholders of the chosen snippet.
+#[derive(Debug, Clone, Default)]
+pub struct SnippetPicker {
+    pub row: usize,
+    pub library: Vec<Snippet>,
+    pub load_error: Option<String>,
+    pub search: String,
+    pub chosen: Option<usize>,
+    pub values: HashMap<String, String>,
+}
+
+impl SnippetPicker {
+    pub fn chosen_snippet(&self) -> Option<&Snippet> {
+        self.chosen.and_then(|idx| self.library.get(idx))
+    }
+}
+
+// Modes right before and after a move, copy, deletion, import or bulk edit.
+// Undo reverts only the difference, so later edits are kept.
+#[derive(Debug, Clone)]
+pub struct UndoStep {
+    pub label: String,
+    pub before: Vec<AppMode>,
+    pub after: Vec<AppMode>,
+    // Id of the mode that was selected before.
+    pub selected_mode: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsField {
+    ConfigPath,
+    StatePath,
+    DialogDir,
+    BackupCount,
+    CheckCommand,
+}
+
+// Text fields of the settings screen, kept as typed until saved.
+#[derive(Debug, Clone, Default)]
+pub struct SettingsForm {
+    pub config_path: String,
+    pub state_path: String,
+    pub dialog_dir: String,
+    pub backup_count: String,
+    pub check_command: String,
+    pub reload_method: ReloadMethod,
+}
+
+impl SettingsForm {
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        Self {
+            config_path: settings.config_path.as_ref().map(|p| p.display().to_string()).unwrap_or_default(),
+            state_path: settings.state_path.display().to_string(),
+            dialog_dir: settings.dialog_dir.display().to_string(),
+            backup_count: settings.backup_count.to_string(),
+            check_command: settings.check_command.as_ref().map(|p| p.display().to_string()).unwrap_or_default(),
+            reload_method: settings.reload_method,
+        }
+    }
+
+    pub fn edit(&mut self, field: SettingsField, value: String) {
+        match field {
+            SettingsField::ConfigPath => self.config_path = value,
+            SettingsField::StatePath => self.state_path = value,
+            SettingsField::DialogDir => self.dialog_dir = value,
+            SettingsField::BackupCount => self.backup_count = value,
+            SettingsField::CheckCommand => self.check_command = value,
+        }
+    }
+
+    pub fn to_settings(&self) -> Result<AppSettings, String> {
+        let path = |label: &str, value: &str| {
+            let value = value.trim();
+            if value.is_empty() {
+                Err(format!("{label} can't be empty"))
+            } else {
+                Ok(PathBuf::from(value))
+            }
+        };
+        Ok(AppSettings {
+            config_path: Some(self.config_path.trim()).filter(|p| !p.is_empty()).map(PathBuf::from),
+            state_path: path("State file", &self.state_path)?,
+            dialog_dir: path("Dialog directory", &self.dialog_dir)?,
+            reload_method: self.reload_method,
+            backup_count: self
+                .backup_count
+                .trim()
+                .parse()
+                .map_err(|_| format!("Backups to keep must be a number, got '{}'", self.backup_count.trim()))?,
+            check_command: Some(self.check_command.trim()).filter(|p| !p.is_empty()).map(PathBuf::from),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapScope {
+    AllModes,
+    Mode(usize, String),
+}
+
+impl std::fmt::Display for MapScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MapScope::AllModes => f.write_str("All modes"),
+            MapScope::Mode(_, name) => f.write_str(name),
+        }
+    }
+}
+
+// Destructive actions wait for one of these to be confirmed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Confirmation {
+    DeleteMode(usize),
+    DeleteHotkey(usize),
+    DeleteSelected,
+    ReloadFromDisk,
+    SwitchProfile(String),
+}
+
+impl Confirmation {
+    // The question and the label of the button that goes ahead.
+    fn prompt(&self, state: &AppState, ui: &ViewState) -> (String, &'static str) {
+        let mode = state.modes.get(state.selected_mode);
+        let mode_name = mode.map(|m| m.name.as_str()).unwrap_or_default();
+        match self {
+            Confirmation::DeleteMode(idx) => match state.modes.get(*idx) {
+                Some(m) => (format!("Delete the mode \"{}\" and its {} hotkey(s)?", m.name, m.hotkeys.len()), "Delete Mode"),
+                None => ("Delete this mode?".to_string(), "Delete Mode"),
+            },
+            Confirmation::DeleteHotkey(idx) => {
+                let combo = mode.and_then(|m| m.hotkeys.get(*idx)).map(GuiHotkey::combo_string).unwrap_or_default();
+                (format!("Delete the hotkey \"{combo}\" from \"{mode_name}\"?"), "Delete")
+            }
+            Confirmation::DeleteSelected => (
+                format!("Delete {} selected hotkey(s) from \"{mode_name}\"?", ui.selected_rows.len()),
+                "Delete",
+            ),
+            Confirmation::ReloadFromDisk => (
+                "Replace every mode with the config on disk? Changes made in the GUI since the last save are lost.".to_string(),
+                "Reload",
+            ),
+            Confirmation::SwitchProfile(name) => (
+                format!("No profile is active, so the current modes aren't saved in any profile. Replace them with the profile \"{name}\"?"),
+                "Switch",
+            ),
+        }
+    }
+}
+
+// Where a mode of an imported file goes, as offered in the import wizard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetChoice(pub ImportTarget, pub String);
+
+impl std::fmt::Display for TargetChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.1)
+    }
+}
+
+// A mode offered as the target of a move or copy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModeChoice(pub usize, pub String);
+
+impl std::fmt::Display for ModeChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.1)
+    }
+}
+
+// UI state that only lives for the session and is never persisted.
+#[derive(Debug, Default)]
+pub struct ViewState {
+    pub table: RowView,
+    pub tag_drafts: HashMap<usize, String>,
+    // Rows whose environment and working directory section is open.
+    pub expanded_rows: BTreeSet<usize>,
+    pub show_keyboard_map: bool,
+    pub keyboard_layout: KeyboardLayout,
+    // `None` counts bindings from every mode.
+    pub map_scope: Option<usize>,
+    // Modifiers toggled on the map plus the ones physically held down.
+    pub map_modifiers: BTreeSet<String>,
+    pub held_modifiers: BTreeSet<String>,
+    pub reload_report: Option<ReloadReport>,
+    pub daemon_status: Option<DaemonStatus>,
+    // Watched files that changed on disk since the GUI last read or wrote
+    // them; non-empty shows the reload/keep/merge banner.
+    pub external_changes: Vec<PathBuf>,
+    pub show_settings: bool,
+    pub settings_form: SettingsForm,
+    // Set when command-line options override part of the saved settings.
+    pub settings_overridden: bool,
+    pub config_location: Option<ConfigLocation>,
+    // The last save was stopped because the target is root-owned; offers to
+    // retry through pkexec.
+    pub privileged_save: bool,
+    // pkexec is running; its polkit dialog may be waiting for a password.
+    pub saving_privileged: bool,
+    // A preflight check is running; saving waits for it.
+    pub checking_config: bool,
+    // The window was asked to close with unsaved edits.
+    pub confirm_close: bool,
+    pub save_preview: Option<SavePreview>,
+    pub side_by_side_diff: bool,
+    pub import: Option<ImportWizard>,
+    // Rows of the selected mode picked for bulk actions, and the row a
+    // shift-click extends the selection from.
+    pub selected_rows: BTreeSet<usize>,
+    pub selection_anchor: Option<usize>,
+    pub bulk_tag_draft: String,
+    // Newest last.
+    pub undo: Vec<UndoStep>,
+    // Saved profile names, and the one loaded last; edits to it are saved
+    // back before switching to another.
+    pub profiles: Vec<String>,
+    pub active_profile: Option<String>,
+    pub profile_draft: String,
+    pub run: Option<RunPanel>,
+    pub snippets: Option<SnippetPicker>,
+    // While set, the window only shows the confirmation dialog.
+    pub confirmation: Option<Confirmation>,
+}
+
+impl ViewState {
+    pub fn active_map_modifiers(&self) -> BTreeSet<String> {
+        self.map_modifiers.union(&self.held_modifiers).cloned().collect()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
     SelectMode(usize),
     EditModeName(String),
     EditCommand(usize, String),
     ToggleActive(usize, bool),
+    EditDescription(usize, String),
+    EditTagDraft(usize, String),
+    AddTag(usize),
+    RemoveTag(usize, String),
+    ToggleEnvironment(usize),
+    EditWorkingDir(usize, String),
+    AddEnvVar(usize),
+    EditEnvName(usize, usize, String),
+    EditEnvValue(usize, usize, String),
+    RemoveEnvVar(usize, usize),
+    EditSearch(String),
+    FilterTag(Option<String>),
+    SortBy(SortColumn),
+    FilterRows(RowFilter),
+    ToggleKeyboardMap,
+    SelectKeyboardLayout(KeyboardLayout),
+    SelectMapScope(MapScope),
+    ToggleMapModifier(String),
+    ModifiersChanged(iced::keyboard::Modifiers),
+    KeyMapClicked(String),
+    ReloadFinished(ReloadReport),
+    RefreshDaemonStatus,
+    DaemonStatusRefreshed(Option<DaemonStatus>),
+    ConfigChangedOnDisk,
+    ReloadFromDisk,
+    KeepMine,
+    MergeWithDisk,
+    ToggleSettings,
+    EditSettingsField(SettingsField, String),
+    SelectReloadMethod(ReloadMethod),
+    SaveSettings,
+    SavePrivileged,
+    // The text pkexec was given and whether it was written.
+    PrivilegedSaveFinished(String, Result<(), String>),
+    CancelPrivilegedSave,
+    ConfirmSave,
+    CancelSave,
+    ToggleDiffLayout,
+    CloseRequested,
+    SaveAndQuit,
+    DiscardAndQuit,
+    CancelQuit,
+    ReorderHotkey(usize, usize),
+    ReorderMode(usize, usize),
+    MoveHotkey(usize, usize),
+    CopyHotkey(usize, usize),
+    RowClicked(usize),
+    SelectAllRows,
+    ClearSelection,
+    ActivateSelected,
+    DeactivateSelected,
+    DeleteSelected,
+    MoveSelected(usize),
+    EditBulkTag(String),
+    TagSelected,
+    UntagSelected(String),
+    Undo,
     DeleteHotkey(usize),
     DeleteMode(usize),
     AddHotkey,
     StartRecording(usize),
     KeyRecorded(String),
     StopRecording,
+    RunCommand(usize),
+    RunFinished(RunOutput),
+    KillRun,
+    CloseRunOutput,
+    OpenSnippets(usize),
+    EditSnippetSearch(String),
+    ChooseSnippet(usize),
+    EditSnippetValue(String, String),
+    // Replaces the row's command when false, appends to it when true.
+    InsertSnippet(bool),
+    CloseSnippets,
+    Confirm,
+    CancelConfirmation,
+    SwitchProfile(String),
+    EditProfileName(String),
+    SaveProfile,
     AddMode,
+    AddModeFromTemplate(ModeTemplate),
+    DuplicateMode(usize),
     SaveConfig,
+    // The text that was checked and the result.
+    ConfigChecked(AfterCheck, String, Result<(), String>),
     ShowError(String),
     ClearError,
     OpenBinaryPicker(usize),
     BinaryPicked(usize, Option<String>),
     LoadConfigFile,
     ConfigFilePicked(Option<String>),
+    SetImportTarget(usize, ImportTarget),
+    SetImportPolicy(ConflictPolicy),
+    ConfirmImport,
+    CancelImport,
     SaveConfigAs,
     ConfigFileSavePath(Option<String>),
+    ImportShortcutsFile,
+    ShortcutsFilePicked(Option<String>),
+    ExportCheatSheet,
+    CheatSheetSavePath(Option<String>),
+}
+
+fn import_panel<'a>(wizard: &'a ImportWizard, state: &'a AppState) -> Element<'a, Message> {
+    let mut mapping = column![].spacing(8);
+    for (i, (mode, target)) in wizard.plan.modes.iter().zip(&wizard.plan.targets).enumerate() {
+        let mut choices: Vec<TargetChoice> = state
+            .modes
+            .iter()
+            .enumerate()
+            .map(|(idx, m)| TargetChoice(ImportTarget::Existing(idx), format!("Into \"{}\"", m.name)))
+            .collect();
+        choices.push(TargetChoice(ImportTarget::New(mode.name.trim().to_string()), format!("New mode \"{}\"", mode.name.trim())));
+        choices.push(TargetChoice(ImportTarget::Ignore, "Don't import".to_string()));
+        let selected = choices.iter().find(|c| c.0 == *target).cloned();
+        mapping = mapping.push(
+            row![
+                text(format!("{} ({} hotkeys)", mode.name, mode.hotkeys.len()))
+                    .size(15)
+                    .style(TEXT_PRIMARY)
+                    .width(Length::FillPortion(2)),
+                text("→").size(15).style(ACCENT),
+                pick_list(choices, selected, move |choice: TargetChoice| Message::SetImportTarget(i, choice.0))
+                    .padding(6)
+                    .width(Length::FillPortion(3)),
+            ]
+            .spacing(10)
+            .align_items(Alignment::Center),
+        );
+    }
+
+    let result = wizard.plan.apply(state);
+    let mut preview = column![].spacing(4);
+    for row in &result.rows {
+        let color = match row.outcome {
+            ImportOutcome::Added => SUCCESS,
+            ImportOutcome::Overwrote => ERROR,
+            ImportOutcome::AlreadyPresent | ImportOutcome::Skipped | ImportOutcome::AddedInactive => ACCENT,
+        };
+        preview = preview.push(
+            row![
+                text(&row.mode).size(14).style(ACCENT).width(Length::FillPortion(2)),
+                text(&row.combo).size(14).style(TEXT_PRIMARY).width(Length::FillPortion(3)),
+                text(&row.command).size(14).style(TEXT_PRIMARY).width(Length::FillPortion(4)),
+                text(row.outcome.to_string()).size(14).style(color).width(Length::FillPortion(2)),
+            ]
+            .spacing(8),
+        );
+    }
+    if result.rows.is_empty() {
+        preview = preview.push(text("Nothing to import").size(14).style(ACCENT));
+    }
+
+    container(
+        column![
+            text(format!("Import {}", wizard.path)).size(18).style(TEXT_PRIMARY),
+            mapping,
+            row![
+                text("Conflicts:").size(15).style(TEXT_PRIMARY),
+                pick_list(&ConflictPolicy::ALL[..], Some(wizard.plan.policy), Message::SetImportPolicy).padding(6),
+            ]
+            .spacing(10)
+            .align_items(Alignment::Center),
+            text("Preview").size(16).style(TEXT_PRIMARY),
+            scrollable(preview).height(Length::Fill),
+            row![
+                Space::with_width(Length::Fill),
+                button(text("Cancel").style(TEXT_PRIMARY))
+                    .on_press(Message::CancelImport)
+                    .padding([8, 16])
+                    .style(iced::theme::Button::Custom(Box::new(CardButton))),
+                button(text("Import").style(TEXT_PRIMARY))
+                    .on_press(Message::ConfirmImport)
+                    .padding([8, 16])
+                    .style(iced::theme::Button::Custom(Box::new(CardButton))),
+            ]
+            .spacing(10),
+        ]
+        .spacing(14),
+    )
+    .padding(16)
+    .height(Length::Fill)
+    .style(iced::theme::Container::Custom(Box::new(CardContainer)))
+    .into()
+}
+
+fn environment_label(action: &GuiAction) -> String {
+    let set = action.env.len() + usize::from(action.working_dir.is_some());
+    if set == 0 {
+        "Environment".to_string()
+    } else {
+        format!("Environment ({set})")
+    }
+}
+
+fn environment_section<'a>(i: usize, action: &'a GuiAction) -> Element<'a, Message> {
+    let mut vars = column![].spacing(6);
+    for (v, var) in action.env.iter().enumerate() {
+        vars = vars.push(
+            row![
+                text_input("NAME", &var.name)
+                    .on_input(move |val| Message::EditEnvName(i, v, val))
+                    .padding(6)
+                    .size(14)
+                    .font(iced::Font::MONOSPACE)
+                    .width(Length::Fixed(200.0))
+                    .style(iced::theme::TextInput::Custom(Box::new(PastelTextInput))),
+                text("=").size(14).style(ACCENT),
+                text_input("value, e.g. $HOME/bin:$PATH", &var.value)
+                    .on_input(move |val| Message::EditEnvValue(i, v, val))
+                    .padding(6)
+                    .size(14)
+                    .font(iced::Font::MONOSPACE)
+                    .style(iced::theme::TextInput::Custom(Box::new(PastelTextInput))),
+                button(text("×").size(14).style(TEXT_PRIMARY))
+                    .on_press(Message::RemoveEnvVar(i, v))
+                    .padding([4, 10])
+                    .style(iced::theme::Button::Custom(Box::new(CardButton))),
+            ]
+            .spacing(8)
+            .align_items(Alignment::Center),
+        );
+    }
+    let preview = action.shell_command();
+    let preview: Element<'a, Message> = if preview == action.command {
+        Space::with_height(Length::Fixed(0.0)).into()
+    } else {
+        text(preview).size(13).font(iced::Font::MONOSPACE).style(ACCENT).into()
+    };
+
+    container(
+        column![
+            row![
+                text("Working directory").size(14).style(TEXT_PRIMARY).width(Length::Fixed(140.0)),
+                text_input("Unchanged", action.working_dir.as_deref().unwrap_or(""))
+                    .on_input(move |val| Message::EditWorkingDir(i, val))
+                    .padding(6)
+                    .size(14)
+                    .font(iced::Font::MONOSPACE)
+                    .style(iced::theme::TextInput::Custom(Box::new(PastelTextInput))),
+            ]
+            .spacing(8)
+            .align_items(Alignment::Center),
+            vars,
+            row![
+                button(text("Add Variable").size(14).style(TEXT_PRIMARY))
+                    .on_press(Message::AddEnvVar(i))
+                    .padding([6, 12])
+                    .style(iced::theme::Button::Custom(Box::new(CardButton))),
+                Space::with_width(Length::Fill),
+            ],
+            preview,
+        ]
+        .spacing(8),
+    )
+    .padding(10)
+    .width(Length::Fill)
+    .style(iced::theme::Container::Custom(Box::new(CardContainer)))
+    .into()
+}
+
+fn snippet_panel<'a>(picker: &'a SnippetPicker, state: &'a AppState) -> Element<'a, Message> {
+    let combo = state.modes[state.selected_mode]
+        .hotkeys
+        .get(picker.row)
+        .map(|hk| hk.combo_string())
+        .filter(|combo| !combo.is_empty())
+        .unwrap_or_else(|| "the new hotkey".to_string());
+
+    let mut list = column![].spacing(6);
+    for (idx, snippet) in picker.library.iter().enumerate().filter(|(_, s)| s.matches(&picker.search)) {
+        let color = if picker.chosen == Some(idx) { MODIFIER_ON } else { CARD };
+        list = list.push(
+            button(
+                column![
+                    text(&snippet.name).size(15).style(TEXT_PRIMARY),
+                    text(&snippet.command).size(13).font(iced::Font::MONOSPACE).style(ACCENT),
+                    text(&snippet.description).size(13).style(ACCENT),
+                ]
+                .spacing(2),
+            )
+            .on_press(Message::ChooseSnippet(idx))
+            .padding([6, 10])
+            .width(Length::Fill)
+            .style(iced::theme::Button::Custom(Box::new(KeyCapButton(color)))),
+        );
+    }
+
+    let details: Element<'a, Message> = match picker.chosen_snippet() {
+        None => text("Pick a snippet to fill in its placeholders.").size(14).style(ACCENT).into(),
+        Some(snippet) => {
+            let mut fields = column![].spacing(8);
+            for placeholder in snippet.placeholders() {
+                let name = placeholder.name.clone();
+                fields = fields.push(
+                    row![
+                        text(placeholder.name.clone()).size(14).style(TEXT_PRIMARY).width(Length::Fixed(110.0)),
+                        text_input(
+                            placeholder.default.as_deref().unwrap_or(""),
+                            picker.values.get(&placeholder.name).map(String::as_str).unwrap_or(""),
+                        )
+                        .on_input(move |val| Message::EditSnippetValue(name.clone(), val))
+                        .padding(6)
+                        .size(14)
+                        .style(iced::theme::TextInput::Custom(Box::new(PastelTextInput))),
+                    ]
+                    .spacing(8)
+                    .align_items(Alignment::Center),
+                );
+            }
+            column![
+                fields,
+                text(snippet.fill(&picker.values)).size(14).font(iced::Font::MONOSPACE).style(TEXT_PRIMARY),
+                row![
+                    Space::with_width(Length::Fill),
+                    button(text("Append").style(TEXT_PRIMARY))
+                        .on_press(Message::InsertSnippet(true))
+                        .padding([8, 16])
+                        .style(iced::theme::Button::Custom(Box::new(CardButton))),
+                    button(text("Replace Command").style(TEXT_PRIMARY))
+                        .on_press(Message::InsertSnippet(false))
+                        .padding([8, 16])
+                        .style(iced::theme::Button::Custom(Box::new(CardButton))),
+                ]
+                .spacing(10),
+            ]
+            .spacing(12)
+            .into()
+        }
+    };
+
+    container(
+        column![
+            row![
+                text(format!("Snippets for {combo}")).size(18).style(TEXT_PRIMARY).width(Length::Fill),
+                button(text("Close").style(TEXT_PRIMARY))
+                    .on_press(Message::CloseSnippets)
+                    .padding([8, 16])
+                    .style(iced::theme::Button::Custom(Box::new(CardButton))),
+            ]
+            .align_items(Alignment::Center),
+            text(picker.load_error.as_deref().unwrap_or_default()).size(14).style(ERROR),
+            text_input("Search snippets", &picker.search)
+                .on_input(Message::EditSnippetSearch)
+                .padding(8)
+                .style(iced::theme::TextInput::Custom(Box::new(PastelTextInput))),
+            row![
+                scrollable(list).height(Length::Fill).width(Length::FillPortion(3)),
+                container(details).width(Length::FillPortion(2)),
+            ]
+            .spacing(16),
+        ]
+        .spacing(12),
+    )
+    .padding(16)
+    .height(Length::Fill)
+    .style(iced::theme::Container::Custom(Box::new(CardContainer)))
+    .into()
+}
+
+fn run_panel<'a>(run: &'a RunPanel) -> Element<'a, Message> {
+    let (status, color, action, message) = match &run.output {
+        None => ("running…".to_string(), ACCENT, "Kill", Message::KillRun),
+        Some(output) => {
+            let color = if output.status == RunStatus::Exited(Some(0)) { SUCCESS } else { ERROR };
+            (output.status.to_string(), color, "Close", Message::CloseRunOutput)
+        }
+    };
+    let stream = |label: &str, content: &'a str| -> Element<'a, Message> {
+        if content.is_empty() {
+            return Space::with_height(Length::Fixed(0.0)).into();
+        }
+        column![
+            text(label).size(13).style(ACCENT),
+            scrollable(text(content).size(13).font(iced::Font::MONOSPACE).style(TEXT_PRIMARY))
+                .height(Length::Fixed(110.0))
+                .width(Length::Fill),
+        ]
+        .spacing(4)
+        .into()
+    };
+    let action_style: Box<dyn button::StyleSheet<Style = Theme>> =
+        if run.output.is_none() { Box::new(DangerButton) } else { Box::new(CardButton) };
+    let (stdout, stderr) = run
+        .output
+        .as_ref()
+        .map_or(("", ""), |output| (output.stdout.as_str(), output.stderr.as_str()));
+
+    container(
+        column![
+            row![
+                text(format!("$ {}", run.command))
+                    .size(14)
+                    .font(iced::Font::MONOSPACE)
+                    .style(TEXT_PRIMARY)
+                    .width(Length::Fill),
+                text(status).size(14).style(color),
+                button(text(action).size(14))
+                    .on_press(message)
+                    .padding([6, 12])
+                    .style(iced::theme::Button::Custom(action_style)),
+            ]
+            .spacing(10)
+            .align_items(Alignment::Center),
+            stream("stdout", stdout),
+            stream("stderr", stderr),
+        ]
+        .spacing(8),
+    )
+    .padding(10)
+    .width(Length::Fill)
+    .style(iced::theme::Container::Custom(Box::new(CardContainer)))
+    .into()
+}
+
+fn confirmation_dialog<'a>(confirmation: &Confirmation, state: &AppState, ui: &ViewState) -> Element<'a, Message> {
+    let (question, action) = confirmation.prompt(state, ui);
+    let dialog = container(
+        column![
+            text(question).size(18).style(TEXT_PRIMARY),
+            row![
+                Space::with_width(Length::Fill),
+                button(text("Cancel").style(TEXT_PRIMARY))
+                    .on_press(Message::CancelConfirmation)
+                    .padding([8, 16])
+                    .style(iced::theme::Button::Custom(Box::new(CardButton))),
+                button(text(action))
+                    .on_press(Message::Confirm)
+                    .padding([8, 16])
+                    .style(iced::theme::Button::Custom(Box::new(DangerButton))),
+            ]
+            .spacing(10),
+        ]
+        .spacing(24),
+    )
+    .padding(24)
+    .max_width(520)
+    .style(iced::theme::Container::Custom(Box::new(CardContainer)));
+
+    container(dialog)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x()
+        .center_y()
+        .into()
+}
+
+fn profile_switcher<'a>(ui: &'a ViewState) -> Element<'a, Message> {
+    column![
+        text(match &ui.active_profile {
+            Some(name) => format!("Profile: {name}"),
+            None => "Profiles".to_string(),
+        })
+        .size(16)
+        .style(TEXT_PRIMARY),
+        pick_list(ui.profiles.clone(), ui.active_profile.clone(), Message::SwitchProfile)
+            .placeholder("Switch profile…")
+            .width(Length::Fill)
+            .padding(8),
+        row![
+            text_input("Save as profile", &ui.profile_draft)
+                .on_input(Message::EditProfileName)
+                .on_submit(Message::SaveProfile)
+                .padding(8)
+                .width(Length::Fill)
+                .style(iced::theme::TextInput::Custom(Box::new(PastelTextInput))),
+            button(text("Save").size(15))
+                .on_press(Message::SaveProfile)
+                .padding([8, 12])
+                .style(iced::theme::Button::Custom(Box::new(CardButton))),
+        ]
+        .spacing(6)
+        .align_items(Alignment::Center),
+    ]
+    .spacing(8)
+    .into()
+}
+
+const REORDER_WIDTH: f32 = 26.0;
+
+// Up and down arrows; a missing message leaves that arrow disabled.
+fn reorder_buttons<'a>(up: Option<Message>, down: Option<Message>) -> Element<'a, Message> {
+    let arrow = |label: &str, message: Option<Message>| -> iced::widget::Button<'a, Message, iced::Renderer<Theme>> {
+        let mut arrow = button(text(label).size(11).horizontal_alignment(iced::alignment::Horizontal::Center))
+            .padding([1, 4])
+            .width(Length::Fixed(REORDER_WIDTH))
+            .style(iced::theme::Button::Custom(Box::new(CardButton)));
+        if let Some(message) = message {
+            arrow = arrow.on_press(message);
+        }
+        arrow
+    };
+    column![arrow("▲", up), arrow("▼", down)].spacing(2).into()
+}
+
+// Bulk actions for the selected rows and Undo for the last move, copy or
+// bulk edit; empty when there is nothing to offer.
+fn selection_bar<'a>(state: &'a AppState, ui: &'a ViewState, other_modes: &[ModeChoice]) -> Element<'a, Message> {
+    let action = |label: &str, message: Message| -> iced::widget::Button<'a, Message, iced::Renderer<Theme>> {
+        button(text(label).size(14))
+            .on_press(message)
+            .padding([6, 12])
+            .style(iced::theme::Button::Custom(Box::new(CardButton)))
+    };
+    let mut bar = row![].spacing(8).align_items(Alignment::Center);
+    if !ui.selected_rows.is_empty() {
+        let selected_tags: BTreeSet<String> = state
+            .modes
+            .get(state.selected_mode)
+            .into_iter()
+            .flat_map(|mode| mode.hotkeys.iter().enumerate())
+            .filter(|(i, _)| ui.selected_rows.contains(i))
+            .flat_map(|(_, hk)| hk.action.tags.iter().cloned())
+            .collect();
+        bar = bar
+            .push(text(format!("{} selected", ui.selected_rows.len())).size(15).style(TEXT_PRIMARY))
+            .push(action("Activate", Message::ActivateSelected))
+            .push(action("Deactivate", Message::DeactivateSelected))
+            .push(
+                button(text("Delete").size(14))
+                    .on_press(Message::DeleteSelected)
+                    .padding([6, 12])
+                    .style(iced::theme::Button::Custom(Box::new(DangerButton))),
+            )
+            .push(
+                pick_list(other_modes.to_vec(), None, |to: ModeChoice| Message::MoveSelected(to.0))
+                    .placeholder("Move to…")
+                    .padding(6)
+                    .text_size(14)
+                    .width(Length::Fixed(120.0)),
+            )
+            .push(
+                text_input("Add tag", &ui.bulk_tag_draft)
+                    .on_input(Message::EditBulkTag)
+                    .on_submit(Message::TagSelected)
+                    .padding(6)
+                    .size(14)
+                    .width(Length::Fixed(110.0))
+                    .style(iced::theme::TextInput::Custom(Box::new(PastelTextInput))),
+            )
+            .push(
+                pick_list(selected_tags.into_iter().collect::<Vec<_>>(), None, Message::UntagSelected)
+                    .placeholder("Remove tag…")
+                    .padding(6)
+                    .text_size(14)
+                    .width(Length::Fixed(120.0)),
+            )
+            .push(action("Select all", Message::SelectAllRows))
+            .push(action("Clear", Message::ClearSelection));
+    }
+    if let Some(step) = ui.undo.last() {
+        bar = bar
+            .push(Space::with_width(Length::Fill))
+            .push(action(&format!("Undo {}", step.label), Message::Undo));
+    }
+    if ui.selected_rows.is_empty() && ui.undo.is_empty() {
+        return Space::with_height(Length::Fixed(0.0)).into();
+    }
+    column![bar, Space::with_height(Length::Fixed(8.0))].into()
+}
+
+fn keyboard_map_panel<'a>(state: &'a AppState, ui: &'a ViewState) -> Element<'a, Message> {
+    let modifiers = ui.active_map_modifiers();
+    let counts = keyboard_map::binding_counts(state, ui.map_scope, &modifiers);
+
+    let mut scope_options = vec![MapScope::AllModes];
+    scope_options.extend(
+        state
+            .modes
+            .iter()
+            .enumerate()
+            .map(|(i, mode)| MapScope::Mode(i, mode.name.clone())),
+    );
+    let selected_scope = match ui.map_scope {
+        Some(i) => state.modes.get(i).map(|mode| MapScope::Mode(i, mode.name.clone())),
+        None => Some(MapScope::AllModes),
+    };
+    let held = if modifiers.is_empty() {
+        "No modifiers".to_string()
+    } else {
+        modifiers.iter().cloned().collect::<Vec<_>>().join(" + ")
+    };
+    let toolbar = row![
+        text("Layout").size(15).style(TEXT_PRIMARY),
+        pick_list(&KeyboardLayout::ALL[..], Some(ui.keyboard_layout), Message::SelectKeyboardLayout).padding(6),
+        text("Bindings from").size(15).style(TEXT_PRIMARY),
+        pick_list(scope_options, selected_scope, Message::SelectMapScope).padding(6),
+        Space::with_width(Length::Fill),
+        text(held).size(15).style(ACCENT),
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center);
+
+    let mut keyboard = column![].spacing(KEY_SPACING);
+    for keys in keyboard_map::rows(ui.keyboard_layout) {
+        let mut key_row = row![].spacing(KEY_SPACING);
+        for cap in keys {
+            let width = KEY_UNIT * cap.width + f32::from(KEY_SPACING) * (cap.width - 1.0);
+            let (background, label, message) = if cap.is_modifier() {
+                let background = if modifiers.contains(cap.key) { MODIFIER_ON } else { CARD };
+                (background, cap.label.to_string(), Message::ToggleMapModifier(cap.key.to_string()))
+            } else {
+                let count = counts.get(cap.key).copied().unwrap_or(0);
+                let background = match count {
+                    0 => KEY_FREE,
+                    1 => KEY_USED,
+                    _ => KEY_CONFLICT,
+                };
+                let label = if count > 0 { format!("{} ({})", cap.label, count) } else { cap.label.to_string() };
+                (background, label, Message::KeyMapClicked(cap.key.to_string()))
+            };
+            key_row = key_row.push(
+                button(
+                    text(label)
+                        .size(12)
+                        .horizontal_alignment(iced::alignment::Horizontal::Center)
+                        .width(Length::Fill),
+                )
+                .on_press(message)
+                .width(Length::Fixed(width))
+                .height(Length::Fixed(KEY_UNIT))
+                .padding(4)
+                .style(iced::theme::Button::Custom(Box::new(KeyCapButton(background)))),
+            );
+        }
+        keyboard = keyboard.push(key_row);
+    }
+
+    let swatch = |color: Color, label: &'static str| -> iced::widget::Row<'a, Message, iced::Renderer<Theme>> {
+        row![
+            container(Space::new(Length::Fixed(16.0), Length::Fixed(16.0)))
+                .style(iced::theme::Container::Custom(Box::new(SwatchContainer(color)))),
+            text(label).size(14).style(TEXT_PRIMARY),
+        ]
+        .spacing(6)
+        .align_items(Alignment::Center)
+    };
+    let legend = row![
+        swatch(KEY_FREE, "Free"),
+        swatch(KEY_USED, "One binding"),
+        swatch(KEY_CONFLICT, "Several bindings"),
+        Space::with_width(Length::Fill),
+        text("Hold or toggle modifiers, then click a key to start a new hotkey on that combo.")
+            .size(14)
+            .style(ACCENT),
+    ]
+    .spacing(16)
+    .align_items(Alignment::Center);
+
+    container(column![toolbar, keyboard, legend].spacing(16))
+        .padding(16)
+        .width(Length::Fill)
+        .style(iced::theme::Container::Custom(Box::new(CardContainer)))
+        .into()
+}
+
+fn modified_ago(time: std::time::SystemTime) -> String {
+    let secs = time.elapsed().map(|d| d.as_secs()).unwrap_or(0);
+    match secs {
+        0..=59 => format!("{secs}s ago"),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+fn settings_panel<'a>(ui: &'a ViewState) -> Element<'a, Message> {
+    let form = &ui.settings_form;
+    let field = |label: &str, placeholder: &str, value: &'a str, field: SettingsField| -> iced::widget::Row<'a, Message, iced::Renderer<Theme>> {
+        row![
+            text(label).size(15).style(TEXT_PRIMARY).width(Length::Fixed(170.0)),
+            text_input(placeholder, value)
+                .on_input(move |v| Message::EditSettingsField(field, v))
+                .padding(8)
+                .style(iced::theme::TextInput::Custom(Box::new(PastelTextInput))),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center)
+    };
+
+    let mut panel = column![
+        text(format!("Saved in {}", settings::settings_file().display())).size(14).style(ACCENT),
+        field(
+            "Config file",
+            "Automatic: $XDG_CONFIG_HOME, ~/.config, then /etc/swhkd",
+            &form.config_path,
+            SettingsField::ConfigPath,
+        ),
+        field("State file", "", &form.state_path, SettingsField::StatePath),
+        field("Dialogs start in", "", &form.dialog_dir, SettingsField::DialogDir),
+        row![
+            text("Reload swhkd with").size(15).style(TEXT_PRIMARY).width(Length::Fixed(170.0)),
+            pick_list(&ReloadMethod::ALL[..], Some(form.reload_method), Message::SelectReloadMethod).padding(6),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+        field("Backups to keep", "", &form.backup_count, SettingsField::BackupCount),
+        field(
+            "Extra config check",
+            "None: only the swhkd grammar is checked",
+            &form.check_command,
+            SettingsField::CheckCommand,
+        ),
+        text(format!(
+            "Run as `<program> <candidate file>` before saving; a non-zero exit blocks the save. swhkd has no check-only mode, so use your own script. {} overrides this.",
+            preflight::CHECK_COMMAND_ENV
+        ))
+        .size(13)
+        .style(TEXT_PRIMARY),
+    ]
+    .spacing(12);
+    if ui.settings_overridden {
+        panel = panel.push(
+            text("Command-line options override some of these for this run; saving keeps the values shown here.")
+                .size(14)
+                .style(ACCENT),
+        );
+    }
+    panel = panel.push(
+        button(text("Save Settings").style(TEXT_PRIMARY))
+            .on_press(Message::SaveSettings)
+            .padding([8, 14])
+            .style(iced::theme::Button::Custom(Box::new(CardButton))),
+    );
+
+    container(panel)
+        .padding(16)
+        .width(Length::Fill)
+        .style(iced::theme::Container::Custom(Box::new(CardContainer)))
+        .into()
+}
+
+fn config_location_line<'a>(location: &ConfigLocation) -> Element<'a, Message> {
+    let color = match location.source {
+        ConfigSource::System | ConfigSource::NewFile => ACCENT,
+        _ => TEXT_PRIMARY,
+    };
+    text(format!("Editing {} ({})", location.path.display(), location.source))
+        .size(14)
+        .style(color)
+        .into()
+}
+
+// Disabled while a preflight check runs, so the same save isn't started twice.
+fn save_button<'a>(label: &str, message: Message, checking: bool) -> iced::widget::Button<'a, Message, iced::Renderer<Theme>> {
+    let save = button(text(label).style(TEXT_PRIMARY))
+        .padding(14)
+        .style(iced::theme::Button::Custom(Box::new(CardButton)))
+        .width(Length::FillPortion(1));
+    if checking {
+        save
+    } else {
+        save.on_press(message)
+    }
+}
+
+fn privileged_save_banner<'a>() -> Element<'a, Message> {
+    container(
+        row![
+            text("This config is owned by root. Save it through pkexec? Backups are skipped for privileged saves.")
+                .size(15)
+                .style(ERROR)
+                .width(Length::Fill),
+            button(text("Save with pkexec").size(14).style(TEXT_PRIMARY))
+                .on_press(Message::SavePrivileged)
+                .padding([6, 12])
+                .style(iced::theme::Button::Custom(Box::new(CardButton))),
+            button(text("Cancel").size(14).style(TEXT_PRIMARY))
+                .on_press(Message::CancelPrivilegedSave)
+                .padding([6, 12])
+                .style(iced::theme::Button::Custom(Box::new(CardButton))),
+        ]
+        .spacing(8)
+        .align_items(Alignment::Center),
+    )
+    .padding(10)
+    .width(Length::Fill)
+    .style(iced::theme::Container::Custom(Box::new(CardContainer)))
+    .into()
+}
+
+fn diff_text<'a>(line: Option<&DiffLine>) -> Element<'a, Message> {
+    let (content, color) = match line {
+        Some(DiffLine::Same(l)) => (format!("  {l}"), TEXT_PRIMARY),
+        Some(DiffLine::Removed(l)) => (format!("- {l}"), ERROR),
+        Some(DiffLine::Added(l)) => (format!("+ {l}"), SUCCESS),
+        None => (String::new(), TEXT_PRIMARY),
+    };
+    text(content).size(14).font(iced::Font::MONOSPACE).style(color).into()
+}
+
+fn save_preview_panel<'a>(preview: &'a SavePreview, side_by_side: bool) -> Element<'a, Message> {
+    let mut rows = column![].spacing(2);
+    if !diff::has_changes(&preview.lines) {
+        rows = rows.push(text("The file on disk already has this content.").size(15).style(ACCENT));
+    } else if side_by_side {
+        rows = rows.push(
+            row![
+                text("On disk").size(15).style(ACCENT).width(Length::FillPortion(1)),
+                text("To be written").size(15).style(ACCENT).width(Length::FillPortion(1)),
+            ]
+            .spacing(12),
+        );
+        for (old, new) in diff::side_by_side(&preview.lines) {
+            rows = rows.push(
+                row![
+                    container(diff_text(old.filter(|l| !matches!(l, DiffLine::Added(_))))).width(Length::FillPortion(1)),
+                    container(diff_text(new.filter(|l| !matches!(l, DiffLine::Removed(_))))).width(Length::FillPortion(1)),
+                ]
+                .spacing(12),
+            );
+        }
+    } else {
+        for line in diff::unified(&preview.lines, 3) {
+            rows = rows.push(match line {
+                Some(line) => diff_text(Some(line)),
+                None => text("  …").size(14).font(iced::Font::MONOSPACE).style(ACCENT).into(),
+            });
+        }
+    }
+
+    let action = |label: &str, message: Message| -> iced::widget::Button<'a, Message, iced::Renderer<Theme>> {
+        button(text(label).size(14).style(TEXT_PRIMARY))
+            .on_press(message)
+            .padding([6, 12])
+            .style(iced::theme::Button::Custom(Box::new(CardButton)))
+    };
+
+    container(
+        column![
+            row![
+                text(format!("Changes to {}", preview.path.display())).size(16).style(TEXT_PRIMARY).width(Length::Fill),
+                action(if side_by_side { "Unified" } else { "Side by Side" }, Message::ToggleDiffLayout),
+            ]
+            .spacing(8)
+            .align_items(Alignment::Center),
+            scrollable(rows).height(Length::Fill),
+            row![
+                Space::with_width(Length::Fill),
+                action("Cancel", Message::CancelSave),
+                action("Save & Apply", Message::ConfirmSave),
+            ]
+            .spacing(8),
+        ]
+        .spacing(12),
+    )
+    .padding(16)
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .style(iced::theme::Container::Custom(Box::new(CardContainer)))
+    .into()
+}
+
+fn confirm_close_banner<'a>() -> Element<'a, Message> {
+    let action = |label: &str, message: Message| -> iced::widget::Button<'a, Message, iced::Renderer<Theme>> {
+        button(text(label).size(14).style(TEXT_PRIMARY))
+            .on_press(message)
+            .padding([6, 12])
+            .style(iced::theme::Button::Custom(Box::new(CardButton)))
+    };
+
+    container(
+        row![
+            text("There are unsaved changes. Save them before closing?")
+                .size(15)
+                .style(ERROR)
+                .width(Length::Fill),
+            action("Save & Quit", Message::SaveAndQuit),
+            action("Discard & Quit", Message::DiscardAndQuit),
+            action("Cancel", Message::CancelQuit),
+        ]
+        .spacing(8)
+        .align_items(Alignment::Center),
+    )
+    .padding(10)
+    .width(Length::Fill)
+    .style(iced::theme::Container::Custom(Box::new(CardContainer)))
+    .into()
+}
+
+// Reload and Merge only apply to the config itself; included files aren't
+// edited here, so changes to them are just acknowledged.
+fn external_change_banner<'a>(files: &[PathBuf], config: Option<&PathBuf>) -> Element<'a, Message> {
+    let names = files.iter().map(|f| f.display().to_string()).collect::<Vec<_>>().join(", ");
+    let config_changed = config.is_some_and(|config| files.contains(config));
+    let action = |label: &str, message: Message| -> iced::widget::Button<'a, Message, iced::Renderer<Theme>> {
+        button(text(label).size(14).style(TEXT_PRIMARY))
+            .on_press(message)
+            .padding([6, 12])
+            .style(iced::theme::Button::Custom(Box::new(CardButton)))
+    };
+
+    let content = if config_changed {
+        row![
+            text(format!("Changed outside the GUI: {names}"))
+                .size(15)
+                .style(ERROR)
+                .width(Length::Fill),
+            action("Reload", Message::ReloadFromDisk),
+            action("Keep Mine", Message::KeepMine),
+            action("Merge", Message::MergeWithDisk),
+        ]
+    } else {
+        row![
+            text(format!(
+                "Included file changed outside the GUI: {names}. Saving keeps the include, so the change stays; reload swhkd to use it."
+            ))
+            .size(15)
+            .style(ACCENT)
+            .width(Length::Fill),
+            action("OK", Message::KeepMine),
+        ]
+    };
+
+    container(content.spacing(8).align_items(Alignment::Center))
+    .padding(10)
+    .width(Length::Fill)
+    .style(iced::theme::Container::Custom(Box::new(CardContainer)))
+    .into()
 }
 
-pub fn view<'a>(state: &'a AppState, error: &'a Option<String>) -> Element<'a, Message> {
+fn daemon_status_panel<'a>(status: &DaemonStatus) -> Element<'a, Message> {
+    let process_line = |name: &str, info: &Option<crate::daemon::ProcessInfo>| -> iced::widget::Text<'a, iced::Renderer<Theme>> {
+        match info {
+            Some(p) => text(format!("{name}: running (pid {})", p.pid)).size(14).style(SUCCESS),
+            None => text(format!("{name}: not running")).size(14).style(ERROR),
+        }
+    };
+    let (config_note, config_color) = match status.config_state {
+        ConfigState::Matches => ("matches last save", SUCCESS),
+        ConfigState::NotSaved => ("not saved from the GUI yet", ACCENT),
+        ConfigState::Modified => ("changed since last save", ERROR),
+        ConfigState::OtherFile => ("differs from the file the GUI writes", ERROR),
+        ConfigState::Missing => ("file missing", ERROR),
+    };
+    let modified = status
+        .config_modified
+        .map(|t| format!(", modified {}", modified_ago(t)))
+        .unwrap_or_default();
+
+    container(
+        column![
+            process_line("swhkd", &status.swhkd),
+            process_line("swhks", &status.swhks),
+            text(format!("config: {}{}", status.config_path.display(), modified)).size(14).style(TEXT_PRIMARY),
+            text(config_note).size(14).style(config_color),
+        ]
+        .spacing(2),
+    )
+    .padding(10)
+    .style(iced::theme::Container::Custom(Box::new(CardContainer)))
+    .into()
+}
+
+// `saved_modes` is the last saved snapshot; modes that differ from it are
+// marked as modified.
+pub fn view<'a>(state: &'a AppState, error: &'a Option<String>, ui: &'a ViewState, saved_modes: &'a [AppMode]) -> Element<'a, Message> {
+    if let Some(confirmation) = &ui.confirmation {
+        return confirmation_dialog(confirmation, state, ui);
+    }
     
     let mut mode_list = column![];
     for (i, mode) in state.modes.iter().enumerate() {
@@ -130,8 +1389,9 @@ pub fn view<'a>(state: &'a AppState, error: &'a Option<String>) -> Element<'a, M
             .padding(4)
             .width(Length::Fixed(34.0))
             .height(Length::Fixed(34.0));
+        let label = if saved_modes.contains(mode) { mode.name.clone() } else { format!("{} •", mode.name) };
         let mode_button = button(
-            text(&mode.name)
+            text(label)
                 .style(TEXT_PRIMARY)
                 .size(18),
         )
@@ -139,7 +1399,14 @@ pub fn view<'a>(state: &'a AppState, error: &'a Option<String>) -> Element<'a, M
         .width(Length::FillPortion(1))
         .padding(9)
         .style(iced::theme::Button::Custom(Box::new(CardButton)));
-        let mode_row = row![mode_button, delete_button]
+        let mode_row = row![
+            reorder_buttons(
+                i.checked_sub(1).map(|up| Message::ReorderMode(i, up)),
+                (i + 1 < state.modes.len()).then(|| Message::ReorderMode(i, i + 1)),
+            ),
+            mode_button,
+            delete_button
+        ]
             .spacing(7)
             .align_items(Alignment::Center);
         mode_list = mode_list.push(mode_row).push(Space::with_height(Length::Fixed(6.0))); 
@@ -153,8 +1420,16 @@ pub fn view<'a>(state: &'a AppState, error: &'a Option<String>) -> Element<'a, M
         .padding(9)
         .style(iced::theme::Button::Custom(Box::new(CardButton)))
     );
+    mode_list = mode_list.push(Space::with_height(Length::Fixed(6.0))).push(
+        pick_list(&TEMPLATES[..], None, Message::AddModeFromTemplate)
+            .placeholder("Add from template…")
+            .width(Length::Fill)
+            .padding(9),
+    );
    let mode_list = container(
     column![
+        profile_switcher(ui),
+        Space::with_height(Length::Fixed(20.0)),
         
         container(
             text("Modes")
@@ -173,10 +1448,10 @@ pub fn view<'a>(state: &'a AppState, error: &'a Option<String>) -> Element<'a, M
 .style(iced::theme::Container::Custom(Box::new(CardContainer)));
 
 
-    let selected_mode = state
-        .modes
-        .get(state.selected_mode)
-        .unwrap_or(&state.modes[0]);
+    // `AppState::ensure_mode` keeps this in range; the fallback only avoids
+    // a panic should that ever be missed.
+    static NO_MODE: AppMode = AppMode { id: 0, name: String::new(), hotkeys: Vec::new() };
+    let selected_mode = state.modes.get(state.selected_mode).unwrap_or(&NO_MODE);
 
    
     let mode_name_section = container(
@@ -193,6 +1468,10 @@ pub fn view<'a>(state: &'a AppState, error: &'a Option<String>) -> Element<'a, M
                 .style(iced::theme::TextInput::Custom(Box::new(PastelTextInput)))
                 .width(Length::Fixed(180.0))
                 .style(iced::theme::TextInput::Custom(Box::new(PastelTextInput))),
+            button(text("Duplicate").size(15))
+                .on_press(Message::DuplicateMode(state.selected_mode))
+                .padding([8, 14])
+                .style(iced::theme::Button::Custom(Box::new(CardButton))),
         ]
         .align_items(Alignment::Center)
         .spacing(8),
@@ -200,12 +1479,29 @@ pub fn view<'a>(state: &'a AppState, error: &'a Option<String>) -> Element<'a, M
     .padding(12)
     .style(iced::theme::Container::Custom(Box::new(CardContainer)));
 
+    let sort_label = |column: SortColumn, label: &str| match ui.table.sort {
+        Some((c, true)) if c == column => format!("{label} ▲"),
+        Some((c, false)) if c == column => format!("{label} ▼"),
+        _ => label.to_string(),
+    };
+    let sort_button = |column: SortColumn, label: &str| -> iced::widget::Button<'a, Message, iced::Renderer<Theme>> {
+        button(
+            text(sort_label(column, label))
+                .size(15)
+                .style(TEXT_PRIMARY)
+                .horizontal_alignment(iced::alignment::Horizontal::Center)
+                .width(Length::Fill),
+        )
+        .on_press(Message::SortBy(column))
+        .padding(4)
+        .style(iced::theme::Button::Custom(Box::new(CardButton)))
+    };
     let header_row: iced::widget::Container<'_, Message, iced::Renderer<Theme>> = container(
         row![
-            text("Key Combination").width(Length::FillPortion(3)).size(15).style(TEXT_PRIMARY).horizontal_alignment(iced::alignment::Horizontal::Center),
-            text("Key Combination").width(Length::FillPortion(3)).size(15).style(TEXT_PRIMARY).horizontal_alignment(iced::alignment::Horizontal::Center),
-            text("Command").width(Length::FillPortion(4)).size(15).style(TEXT_PRIMARY).horizontal_alignment(iced::alignment::Horizontal::Center),
-            text("Active").width(Length::Fixed(90.0)).size(15).style(TEXT_PRIMARY).horizontal_alignment(iced::alignment::Horizontal::Center),
+            Space::with_width(Length::Fixed(REORDER_WIDTH)),
+            sort_button(SortColumn::Combo, "Key Combination").width(Length::FillPortion(3)),
+            sort_button(SortColumn::Command, "Command").width(Length::FillPortion(4)),
+            sort_button(SortColumn::Active, "Active").width(Length::Fixed(90.0)),
             text("Delete").width(Length::Fixed(90.0)).size(15).horizontal_alignment(iced::alignment::Horizontal::Center),
             text("Record").width(Length::Fixed(90.0)).size(15).horizontal_alignment(iced::alignment::Horizontal::Center),
         ]
@@ -215,10 +1511,60 @@ pub fn view<'a>(state: &'a AppState, error: &'a Option<String>) -> Element<'a, M
     .padding([8, 3, 8, 8])
     .style(iced::theme::Container::Custom(Box::new(CardContainer)));
 
+    let mut tag_options = vec![ALL_TAGS.to_string()];
+    tag_options.extend(
+        selected_mode
+            .hotkeys
+            .iter()
+            .flat_map(|hk| hk.action.tags.iter().cloned())
+            .collect::<BTreeSet<_>>(),
+    );
+    let search_row = row![
+        text_input("Search by keys, command, description or tag", &ui.table.search)
+            .on_input(Message::EditSearch)
+            .padding(8)
+            .width(Length::FillPortion(3))
+            .style(iced::theme::TextInput::Custom(Box::new(PastelTextInput))),
+        pick_list(
+            tag_options,
+            Some(ui.table.tag_filter.clone().unwrap_or_else(|| ALL_TAGS.to_string())),
+            |tag| Message::FilterTag(if tag == ALL_TAGS { None } else { Some(tag) }),
+        )
+        .padding(8)
+        .width(Length::FillPortion(1)),
+        pick_list(&RowFilter::ALL[..], Some(ui.table.filter), Message::FilterRows)
+            .padding(8)
+            .width(Length::FillPortion(1)),
+    ]
+    .spacing(8)
+    .align_items(Alignment::Center);
+
+    let conflicts = state.conflicting_signatures(state.selected_mode);
+    let other_modes: Vec<ModeChoice> = state
+        .modes
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != state.selected_mode)
+        .map(|(i, mode)| ModeChoice(i, mode.name.clone()))
+        .collect();
+    let visible = ui.table.visible_rows(state);
+
     //Hotkey Rows 
     let mut hotkey_rows = column![];
-    for (i, hk) in selected_mode.hotkeys.iter().enumerate() {
+    for (pos, &i) in visible.iter().enumerate() {
+        let hk = &selected_mode.hotkeys[i];
+        let (up, down) = ui.table.reorder_targets(&visible, pos);
+        let (up, down) = (up.map(|to| Message::ReorderHotkey(i, to)), down.map(|to| Message::ReorderHotkey(i, to)));
         let recording = state.recording_hotkey == Some(i);
+        let problem = if recording {
+            None
+        } else if let Some(err) = hk.validation_error() {
+            Some(err)
+        } else if hk.action.active && conflicts.contains(&hk.signature()) {
+            Some("Conflicts with another active binding".to_string())
+        } else {
+            None
+        };
         let key_display = if hk.key.is_empty() && recording {
             "Press a key combination...".to_string()
         } else if hk.modifiers.is_empty() {
@@ -230,17 +1576,20 @@ pub fn view<'a>(state: &'a AppState, error: &'a Option<String>) -> Element<'a, M
                 hk.key
             )
         };
-        let key_cell = container(
+        // Clicking the combo selects the row; shift and ctrl extend the selection.
+        let key_cell = button(
             text(key_display)
                 .size(17)
-                .style(TEXT_PRIMARY)
+                .style(if problem.is_some() { ERROR } else { TEXT_PRIMARY })
                 .horizontal_alignment(iced::alignment::Horizontal::Center)
+                .width(Length::Fill),
         )
+        .on_press(Message::RowClicked(i))
         .padding([12, 0, 12, 0])
         .width(Length::FillPortion(3))
-        .center_x()
-        .center_y()
-        .style(iced::theme::Container::Custom(Box::new(CardContainer)));
+        .style(iced::theme::Button::Custom(Box::new(KeyCapButton(
+            if ui.selected_rows.contains(&i) { MODIFIER_ON } else { CARD },
+        ))));
 
         let file_picker_icon = Image::new("assets/icons8-file-explorer-64.png")
             .width(Length::Fixed(20.0))
@@ -288,6 +1637,7 @@ pub fn view<'a>(state: &'a AppState, error: &'a Option<String>) -> Element<'a, M
     .width(Length::Fixed(90.0));
 
         let hotkey_row = row![
+            reorder_buttons(up, down),
             key_cell,
             command_cell,
             active_box,
@@ -296,9 +1646,105 @@ pub fn view<'a>(state: &'a AppState, error: &'a Option<String>) -> Element<'a, M
         ]
         .spacing(8)
         .align_items(Alignment::Center);
+
+        let mut tag_chips = row![].spacing(6).align_items(Alignment::Center);
+        for tag in &hk.action.tags {
+            tag_chips = tag_chips.push(
+                button(text(format!("{tag}  ×")).size(13).style(TEXT_PRIMARY))
+                    .on_press(Message::RemoveTag(i, tag.clone()))
+                    .padding([3, 8])
+                    .style(iced::theme::Button::Custom(Box::new(CardButton))),
+            );
+        }
+        let details_row = row![
+            text_input("Description", hk.action.description.as_deref().unwrap_or(""))
+                .on_input(move |val| Message::EditDescription(i, val))
+                .padding(6)
+                .size(14)
+                .width(Length::FillPortion(3))
+                .style(iced::theme::TextInput::Custom(Box::new(PastelTextInput))),
+            tag_chips,
+            text_input("Add tag", ui.tag_drafts.get(&i).map(String::as_str).unwrap_or(""))
+                .on_input(move |val| Message::EditTagDraft(i, val))
+                .on_submit(Message::AddTag(i))
+                .padding(6)
+                .size(14)
+                .width(Length::Fixed(120.0))
+                .style(iced::theme::TextInput::Custom(Box::new(PastelTextInput))),
+            button(text(environment_label(&hk.action)).size(14))
+                .on_press(Message::ToggleEnvironment(i))
+                .padding([6, 12])
+                .style(iced::theme::Button::Custom(Box::new(KeyCapButton(
+                    if ui.expanded_rows.contains(&i) { MODIFIER_ON } else { CARD },
+                )))),
+            button(text("Snippets").size(14))
+                .on_press(Message::OpenSnippets(i))
+                .padding([6, 12])
+                .style(iced::theme::Button::Custom(Box::new(CardButton))),
+            button(text("Run").size(14))
+                .on_press(Message::RunCommand(i))
+                .padding([6, 12])
+                .style(iced::theme::Button::Custom(Box::new(CardButton))),
+            pick_list(other_modes.clone(), None, move |to: ModeChoice| Message::MoveHotkey(i, to.0))
+                .placeholder("Move to…")
+                .padding(6)
+                .text_size(14)
+                .width(Length::Fixed(120.0)),
+            pick_list(other_modes.clone(), None, move |to: ModeChoice| Message::CopyHotkey(i, to.0))
+                .placeholder("Copy to…")
+                .padding(6)
+                .text_size(14)
+                .width(Length::Fixed(120.0)),
+            text(problem.unwrap_or_default()).size(14).style(ERROR),
+        ]
+        .spacing(8)
+        .align_items(Alignment::Center);
+
         hotkey_rows = hotkey_rows
             .push(hotkey_row)
-            .push(Space::with_height(Length::Fixed(10.0)));
+            .push(Space::with_height(Length::Fixed(4.0)))
+            .push(details_row);
+        if ui.expanded_rows.contains(&i) {
+            hotkey_rows = hotkey_rows
+                .push(Space::with_height(Length::Fixed(4.0)))
+                .push(environment_section(i, &hk.action));
+        }
+        hotkey_rows = hotkey_rows.push(Space::with_height(Length::Fixed(10.0)));
+    }
+
+    // Search also covers the other modes so a binding can be found without
+    // clicking through every mode first.
+    let matches = ui.table.other_mode_matches(state);
+    if !matches.is_empty() {
+        let mut other_matches = column![].spacing(6);
+        let match_count = matches.len();
+        for (mode_idx, hk) in matches {
+            other_matches = other_matches.push(
+                row![
+                    text(&state.modes[mode_idx].name).size(14).style(ACCENT).width(Length::FillPortion(2)),
+                    text(hk.combo_string()).size(14).style(TEXT_PRIMARY).width(Length::FillPortion(3)),
+                    text(&hk.action.command).size(14).style(TEXT_PRIMARY).width(Length::FillPortion(4)),
+                    button(text("Show").size(14))
+                        .on_press(Message::SelectMode(mode_idx))
+                        .padding([4, 10])
+                        .style(iced::theme::Button::Custom(Box::new(CardButton))),
+                ]
+                .spacing(8)
+                .align_items(Alignment::Center),
+            );
+        }
+        hotkey_rows = hotkey_rows.push(
+            container(
+                column![
+                    text(format!("Matches in other modes ({match_count})")).size(16).style(TEXT_PRIMARY),
+                    other_matches,
+                ]
+                .spacing(10),
+            )
+            .padding(12)
+            .width(Length::Fill)
+            .style(iced::theme::Container::Custom(Box::new(CardContainer))),
+        );
     }
 
      
@@ -309,21 +1755,23 @@ pub fn view<'a>(state: &'a AppState, error: &'a Option<String>) -> Element<'a, M
                 .padding(14)
                 .style(iced::theme::Button::Custom(Box::new(CardButton)))
                 .width(Length::FillPortion(1)),
-            button(text("Load Config").style(TEXT_PRIMARY))
+            button(text("Import Config").style(TEXT_PRIMARY))
                 .on_press(Message::LoadConfigFile)
                 .padding(14)
                 .style(iced::theme::Button::Custom(Box::new(CardButton)))
                 .width(Length::FillPortion(1)),
-            button(text("Save As...").style(TEXT_PRIMARY))
-                .on_press(Message::SaveConfigAs)
+            button(text("Import Shortcuts").style(TEXT_PRIMARY))
+                .on_press(Message::ImportShortcutsFile)
                 .padding(14)
                 .style(iced::theme::Button::Custom(Box::new(CardButton)))
                 .width(Length::FillPortion(1)),
-            button(text("Save & Apply").style(TEXT_PRIMARY))
-                .on_press(Message::SaveConfig)
+            button(text("Cheat Sheet").style(TEXT_PRIMARY))
+                .on_press(Message::ExportCheatSheet)
                 .padding(14)
                 .style(iced::theme::Button::Custom(Box::new(CardButton)))
                 .width(Length::FillPortion(1)),
+            save_button("Save As...", Message::SaveConfigAs, ui.checking_config),
+            save_button(if ui.checking_config { "Checking..." } else { "Save & Apply" }, Message::SaveConfig, ui.checking_config),
         ]
     )
     .padding([16, 0, 0, 0])
@@ -339,16 +1787,95 @@ pub fn view<'a>(state: &'a AppState, error: &'a Option<String>) -> Element<'a, M
             Space::with_height(Length::Fixed(0.0)).into()
         };
 
+    let reload_text: Element<'_, Message> = if let Some(report) = &ui.reload_report {
+            text(report.summary())
+                .style(if report.success { SUCCESS } else { ERROR })
+                .size(15)
+                .into()
+        } else {
+            Space::with_height(Length::Fixed(0.0)).into()
+        };
+
+    let mut banner = column![];
+    if ui.confirm_close {
+        banner = banner.push(confirm_close_banner()).push(Space::with_height(Length::Fixed(12.0)));
+    }
+    if ui.privileged_save {
+        banner = banner.push(privileged_save_banner()).push(Space::with_height(Length::Fixed(12.0)));
+    }
+    if ui.saving_privileged {
+        banner = banner
+            .push(text("Saving through pkexec, waiting for authentication...").size(15).style(ACCENT))
+            .push(Space::with_height(Length::Fixed(12.0)));
+    }
+    if let Some(run) = &ui.run {
+        banner = banner.push(run_panel(run)).push(Space::with_height(Length::Fixed(12.0)));
+    }
+    if !ui.external_changes.is_empty() {
+        banner = banner
+            .push(external_change_banner(&ui.external_changes, ui.config_location.as_ref().map(|c| &c.path)))
+            .push(Space::with_height(Length::Fixed(12.0)));
+    }
+
+    let main_content: Element<'_, Message> = if let Some(preview) = &ui.save_preview {
+        save_preview_panel(preview, ui.side_by_side_diff)
+    } else if let Some(wizard) = &ui.import {
+        import_panel(wizard, state)
+    } else if let Some(picker) = &ui.snippets {
+        snippet_panel(picker, state)
+    } else if ui.show_settings {
+        settings_panel(ui)
+    } else if ui.show_keyboard_map {
+        keyboard_map_panel(state, ui)
+    } else {
+        column![
+            search_row,
+            Space::with_height(Length::Fixed(12.0)),
+            selection_bar(state, ui, &other_modes),
+            header_row,
+            Space::with_height(Length::Fixed(8.0)),
+            scrollable(hotkey_rows),
+        ]
+        .into()
+    };
+
     let right_panel = column![
         mode_name_section,
         Space::with_height(Length::Fixed(24.0)),
-        text("Hotkey Configuration")
-            .size(21)
-            .style(TEXT_PRIMARY),
+        row![
+            text("Hotkey Configuration")
+                .size(21)
+                .style(TEXT_PRIMARY),
+            Space::with_width(Length::Fill),
+            button(text(if ui.show_keyboard_map { "Hotkey Table" } else { "Keyboard Map" }).style(TEXT_PRIMARY))
+                .on_press(Message::ToggleKeyboardMap)
+                .padding([8, 14])
+                .style(iced::theme::Button::Custom(Box::new(CardButton))),
+            button(text(if ui.show_settings { "Close Settings" } else { "Settings" }).style(TEXT_PRIMARY))
+                .on_press(Message::ToggleSettings)
+                .padding([8, 14])
+                .style(iced::theme::Button::Custom(Box::new(CardButton))),
+        ]
+        .spacing(8)
+        .align_items(Alignment::Center),
+        match &ui.config_location {
+            Some(location) => config_location_line(location),
+            None => Space::with_height(Length::Fixed(0.0)).into(),
+        },
             Space::with_height(Length::Fixed(20.0)), // or 24.0, 32.0 etc. as desired
 
-        error_text,
-        scrollable(hotkey_rows),
+        banner,
+
+        row![
+            column![error_text, reload_text].spacing(6).width(Length::Fill),
+            match &ui.daemon_status {
+                Some(status) => daemon_status_panel(status),
+                None => Space::with_width(Length::Shrink).into(),
+            },
+        ]
+        .spacing(12),
+        Space::with_height(Length::Fixed(12.0)),
+        main_content,
         controls,
     ]
     .width(Length::FillPortion(3))
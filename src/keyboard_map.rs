@@ -0,0 +1,241 @@
+use crate::data_model::AppState;
+use std::collections::{BTreeSet, HashMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyboardLayout {
+    #[default]
+    Ansi,
+    Iso,
+}
+
+impl KeyboardLayout {
+    pub const ALL: [KeyboardLayout; 2] = [KeyboardLayout::Ansi, KeyboardLayout::Iso];
+}
+
+impl std::fmt::Display for KeyboardLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            KeyboardLayout::Ansi => "ANSI (US)",
+            KeyboardLayout::Iso => "ISO (EU)",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct KeyCap {
+    // Key name as stored in `GuiHotkey::key`, or the modifier name.
+    pub key: &'static str,
+    pub label: &'static str,
+    // Width in key units, 1.0 being a letter key.
+    pub width: f32,
+}
+
+impl KeyCap {
+    pub fn is_modifier(&self) -> bool {
+        matches!(self.key, "ctrl" | "alt" | "shift" | "super")
+    }
+}
+
+const fn cap(key: &'static str, label: &'static str, width: f32) -> KeyCap {
+    KeyCap { key, label, width }
+}
+
+const FUNCTION_ROW: [KeyCap; 13] = [
+    cap("escape", "Esc", 1.0),
+    cap("f1", "F1", 1.0),
+    cap("f2", "F2", 1.0),
+    cap("f3", "F3", 1.0),
+    cap("f4", "F4", 1.0),
+    cap("f5", "F5", 1.0),
+    cap("f6", "F6", 1.0),
+    cap("f7", "F7", 1.0),
+    cap("f8", "F8", 1.0),
+    cap("f9", "F9", 1.0),
+    cap("f10", "F10", 1.0),
+    cap("f11", "F11", 1.0),
+    cap("f12", "F12", 1.0),
+];
+
+const NUMBER_ROW: [KeyCap; 14] = [
+    cap("grave", "`", 1.0),
+    cap("1", "1", 1.0),
+    cap("2", "2", 1.0),
+    cap("3", "3", 1.0),
+    cap("4", "4", 1.0),
+    cap("5", "5", 1.0),
+    cap("6", "6", 1.0),
+    cap("7", "7", 1.0),
+    cap("8", "8", 1.0),
+    cap("9", "9", 1.0),
+    cap("0", "0", 1.0),
+    cap("minus", "-", 1.0),
+    cap("equal", "=", 1.0),
+    cap("backspace", "Backspace", 2.0),
+];
+
+const TOP_LETTERS: [KeyCap; 12] = [
+    cap("q", "Q", 1.0),
+    cap("w", "W", 1.0),
+    cap("e", "E", 1.0),
+    cap("r", "R", 1.0),
+    cap("t", "T", 1.0),
+    cap("y", "Y", 1.0),
+    cap("u", "U", 1.0),
+    cap("i", "I", 1.0),
+    cap("o", "O", 1.0),
+    cap("p", "P", 1.0),
+    cap("bracketleft", "[", 1.0),
+    cap("bracketright", "]", 1.0),
+];
+
+const HOME_LETTERS: [KeyCap; 11] = [
+    cap("a", "A", 1.0),
+    cap("s", "S", 1.0),
+    cap("d", "D", 1.0),
+    cap("f", "F", 1.0),
+    cap("g", "G", 1.0),
+    cap("h", "H", 1.0),
+    cap("j", "J", 1.0),
+    cap("k", "K", 1.0),
+    cap("l", "L", 1.0),
+    cap("semicolon", ";", 1.0),
+    cap("apostrophe", "'", 1.0),
+];
+
+const BOTTOM_LETTERS: [KeyCap; 10] = [
+    cap("z", "Z", 1.0),
+    cap("x", "X", 1.0),
+    cap("c", "C", 1.0),
+    cap("v", "V", 1.0),
+    cap("b", "B", 1.0),
+    cap("n", "N", 1.0),
+    cap("m", "M", 1.0),
+    cap("comma", ",", 1.0),
+    cap("dot", ".", 1.0),
+    cap("slash", "/", 1.0),
+];
+
+const SPACE_ROW: [KeyCap; 7] = [
+    cap("ctrl", "Ctrl", 1.5),
+    cap("super", "Super", 1.25),
+    cap("alt", "Alt", 1.25),
+    cap("space", "Space", 6.25),
+    cap("alt", "Alt", 1.25),
+    cap("super", "Super", 1.25),
+    cap("ctrl", "Ctrl", 1.5),
+];
+
+const NAVIGATION_ROW: [KeyCap; 11] = [
+    cap("print", "Print", 1.0),
+    cap("insert", "Ins", 1.0),
+    cap("delete", "Del", 1.0),
+    cap("home", "Home", 1.0),
+    cap("end", "End", 1.0),
+    cap("pageup", "PgUp", 1.0),
+    cap("pagedown", "PgDn", 1.0),
+    cap("left", "←", 1.0),
+    cap("up", "↑", 1.0),
+    cap("down", "↓", 1.0),
+    cap("right", "→", 1.0),
+];
+
+pub fn rows(layout: KeyboardLayout) -> Vec<Vec<KeyCap>> {
+    let top = match layout {
+        KeyboardLayout::Ansi => [
+            &[cap("tab", "Tab", 1.5)][..],
+            &TOP_LETTERS,
+            &[cap("backslash", "\\", 1.5)],
+        ]
+        .concat(),
+        // ISO has the tall Enter on the right of the top letter row.
+        KeyboardLayout::Iso => [
+            &[cap("tab", "Tab", 1.5)][..],
+            &TOP_LETTERS,
+            &[cap("enter", "Enter", 1.5)],
+        ]
+        .concat(),
+    };
+    let home = match layout {
+        KeyboardLayout::Ansi => [
+            &[cap("capslock", "Caps", 1.75)][..],
+            &HOME_LETTERS,
+            &[cap("enter", "Enter", 2.25)],
+        ]
+        .concat(),
+        KeyboardLayout::Iso => [
+            &[cap("capslock", "Caps", 1.75)][..],
+            &HOME_LETTERS,
+            &[cap("backslash", "#", 1.0), cap("enter", "", 1.25)],
+        ]
+        .concat(),
+    };
+    let bottom = match layout {
+        KeyboardLayout::Ansi => [
+            &[cap("shift", "Shift", 2.25)][..],
+            &BOTTOM_LETTERS,
+            &[cap("shift", "Shift", 2.75)],
+        ]
+        .concat(),
+        // The extra ISO key next to left shift (KEY_102ND) can't be
+        // recorded or written, so left shift takes its place.
+        KeyboardLayout::Iso => [
+            &[cap("shift", "Shift", 2.25)][..],
+            &BOTTOM_LETTERS,
+            &[cap("shift", "Shift", 2.75)],
+        ]
+        .concat(),
+    };
+
+    vec![
+        FUNCTION_ROW.to_vec(),
+        NUMBER_ROW.to_vec(),
+        top,
+        home,
+        bottom,
+        SPACE_ROW.to_vec(),
+        NAVIGATION_ROW.to_vec(),
+    ]
+}
+
+// iced key codes, by their `Debug` names, and the key name recording them
+// stores. Every key cap on the map is in here.
+pub const RECORDED_KEYS: &[(&str, &str)] = &[
+    ("A", "a"), ("B", "b"), ("C", "c"), ("D", "d"), ("E", "e"), ("F", "f"), ("G", "g"),
+    ("H", "h"), ("I", "i"), ("J", "j"), ("K", "k"), ("L", "l"), ("M", "m"), ("N", "n"),
+    ("O", "o"), ("P", "p"), ("Q", "q"), ("R", "r"), ("S", "s"), ("T", "t"), ("U", "u"),
+    ("V", "v"), ("W", "w"), ("X", "x"), ("Y", "y"), ("Z", "z"),
+    ("Key1", "1"), ("Key2", "2"), ("Key3", "3"), ("Key4", "4"), ("Key5", "5"),
+    ("Key6", "6"), ("Key7", "7"), ("Key8", "8"), ("Key9", "9"), ("Key0", "0"),
+    ("Escape", "escape"), ("Enter", "enter"), ("Tab", "tab"), ("Space", "space"),
+    ("Backspace", "backspace"), ("Capital", "capslock"), ("Grave", "grave"),
+    ("Minus", "minus"), ("Equals", "equal"), ("Backslash", "backslash"),
+    ("LBracket", "bracketleft"), ("RBracket", "bracketright"), ("Semicolon", "semicolon"),
+    ("Apostrophe", "apostrophe"), ("Comma", "comma"), ("Period", "dot"), ("Slash", "slash"),
+    ("F1", "f1"), ("F2", "f2"), ("F3", "f3"), ("F4", "f4"), ("F5", "f5"), ("F6", "f6"),
+    ("F7", "f7"), ("F8", "f8"), ("F9", "f9"), ("F10", "f10"), ("F11", "f11"), ("F12", "f12"),
+    ("Snapshot", "print"), ("Insert", "insert"), ("Delete", "delete"), ("Home", "home"),
+    ("End", "end"), ("PageUp", "pageup"), ("PageDown", "pagedown"),
+    ("Left", "left"), ("Up", "up"), ("Down", "down"), ("Right", "right"),
+];
+
+// `None` for keys that can't be bound.
+pub fn recorded_key(key_code: &str) -> Option<&'static str> {
+    RECORDED_KEYS.iter().find(|(code, _)| *code == key_code).map(|(_, key)| *key)
+}
+
+// Active bindings per key that use exactly `modifiers`, either in one mode or
+// across every mode when `mode` is `None`.
+pub fn binding_counts(state: &AppState, mode: Option<usize>, modifiers: &BTreeSet<String>) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for (idx, app_mode) in state.modes.iter().enumerate() {
+        if mode.is_some_and(|m| m != idx) {
+            continue;
+        }
+        for hk in &app_mode.hotkeys {
+            if hk.action.active && hk.modifiers == *modifiers {
+                *counts.entry(hk.key.clone()).or_insert(0) += 1;
+            }
+        }
+    }
+    counts
+}
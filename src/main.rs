@@ -1,23 +1,366 @@
+mod cheatsheet;
+mod cli;
+mod config_import;
+mod daemon;
 mod data_model;
+mod diff;
+mod discovery;
+mod importers;
 mod interface;
+mod keyboard_map;
+mod merge;
+mod preflight;
+mod profiles;
+mod row_view;
+mod runner;
+mod settings;
+mod snippets;
+mod templates;
+mod undo;
+mod watcher;
 
 
 use iced::{Application, Command, Element, Settings, Theme, Subscription};
-use data_model::{AppState};
-use interface::{view, Message};
-use iced::keyboard::{Event, KeyCode};
+use data_model::{AppState, EnvVar};
+use cheatsheet::CheatSheetFormat;
+use config_import::ImportPlan;
+use interface::{view, AfterCheck, Confirmation, ImportWizard, MapScope, Message, RunPanel, SavePreview, SettingsForm, SnippetPicker, UndoStep, ViewState};
+use settings::{AppSettings, SettingsOverrides};
+use iced::keyboard::Event;
 use rfd::FileDialog;
+use std::time::Duration;
+
+// Moves, copies and bulk edits kept for Undo.
+const UNDO_LIMIT: usize = 20;
 
 
 
 pub fn main() -> iced::Result {
-    SwhkdGui::run(Settings::default())
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (overrides, command) = match cli::parse_overrides(&args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+    let (stored_settings, settings_error) = match AppSettings::load() {
+        Ok(settings) => (settings, None),
+        Err(e) => (AppSettings::default(), Some(e)),
+    };
+    if !command.is_empty() {
+        if let Some(e) = &settings_error {
+            eprintln!("{e}");
+        }
+        std::process::exit(cli::run(&command, &overrides.apply(&stored_settings)));
+    }
+    let mut settings = Settings::with_flags(Startup { stored_settings, overrides, settings_error });
+    // Closing is handled in `update` so unsaved edits can be confirmed.
+    settings.exit_on_close_request = false;
+    SwhkdGui::run(settings)
+}
+
+struct Startup {
+    stored_settings: AppSettings,
+    overrides: SettingsOverrides,
+    settings_error: Option<String>,
 }
 
 
 struct SwhkdGui {
     state: AppState,
     error: Option<String>,
+    ui: ViewState,
+    last_saved: Option<daemon::SavedConfig>,
+    // Watched config files as the GUI last read or wrote them; the base for
+    // telling external edits apart from our own saves.
+    disk: Vec<(std::path::PathBuf, Option<String>)>,
+    // What the settings screen edits and saves; `settings` adds this run's
+    // command-line overrides on top.
+    stored_settings: AppSettings,
+    overrides: SettingsOverrides,
+    settings: AppSettings,
+    config: discovery::ConfigLocation,
+    // Modes as last written to the state file; anything else is unsaved.
+    saved_modes: Vec<data_model::AppMode>,
+    // Set by Save & Quit; the window closes once swhkd has been reloaded.
+    quit_after_reload: bool,
+    // `is_dirty` as of the last message, for the window title.
+    dirty: bool,
+}
+
+
+impl SwhkdGui {
+    // The parser and the extra checker run off the UI thread; `then` says
+    // what to do with the config once it passes.
+    fn check_config(&mut self, then: AfterCheck) -> Command<Message> {
+        let text = self.state.to_swhkd_config_text();
+        let target = match &then {
+            AfterCheck::SaveAs(path) => std::path::PathBuf::from(path),
+            AfterCheck::Preview | AfterCheck::SaveAndQuit => self.config.path.clone(),
+        };
+        let checker = preflight::configured_checker(&self.settings);
+        self.ui.checking_config = true;
+        Command::perform(
+            async move {
+                let checked = text.clone();
+                let result = tokio::task::spawn_blocking(move || preflight::check_config_text(&text, &target, checker.as_deref()))
+                    .await
+                    .unwrap_or_else(|e| Err(format!("The config check failed: {e}")));
+                (checked, result)
+            },
+            move |(text, result)| Message::ConfigChecked(then, text, result),
+        )
+    }
+
+    // Scanning /proc and hashing the config happens off the UI thread.
+    fn refresh_daemon_status(&self) -> Command<Message> {
+        let path = self.config.path.clone();
+        let last_saved = self.last_saved.clone();
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || daemon::status(&path, last_saved.as_ref()))
+                    .await
+                    .ok()
+            },
+            Message::DaemonStatusRefreshed,
+        )
+    }
+
+    fn sync_disk_snapshot(&mut self) {
+        self.disk = watcher::snapshot(&watcher::watched_files(&self.config.path));
+        self.ui.external_changes.clear();
+    }
+
+    fn locate_config(&mut self) {
+        self.config = discovery::locate(&self.stored_settings, &self.overrides);
+        self.ui.config_location = Some(self.config.clone());
+    }
+
+    // Bookkeeping once `written` is on disk, then the reload.
+    fn config_saved(&mut self, written: &str) -> Command<Message> {
+        self.error = None;
+        self.ui.privileged_save = false;
+        let hash = daemon::content_hash(written.as_bytes());
+        self.last_saved = Some(daemon::SavedConfig { path: self.config.path.clone(), hash });
+        self.locate_config();
+        let refresh = self.refresh_daemon_status();
+        self.sync_disk_snapshot();
+        self.ui.reload_report = None;
+        if let Err(e) = self.save_state() {
+            self.error = Some(format!("Config saved, but the GUI state was not: {e}"));
+        }
+        let method = self.settings.reload_method;
+        let reload = Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || daemon::reload(method))
+                    .await
+                    .unwrap_or_else(|e| daemon::ReloadReport {
+                        mode: daemon::DaemonMode::NotRunning,
+                        method: "none".to_string(),
+                        success: false,
+                        stderr: e.to_string(),
+                    })
+            },
+            Message::ReloadFinished,
+        );
+        Command::batch([refresh, reload])
+    }
+
+    fn save_state(&mut self) -> Result<(), String> {
+        self.state.save_to_json_file(&self.settings.state_path.display().to_string())?;
+        self.saved_modes = self.state.modes.clone();
+        Ok(())
+    }
+
+    fn save_preview(&self) -> SavePreview {
+        let on_disk = std::fs::read_to_string(&self.config.path).unwrap_or_default();
+        let text = self.state.to_swhkd_config_text();
+        SavePreview {
+            path: self.config.path.clone(),
+            lines: diff::diff_lines(&on_disk, &text),
+            text,
+        }
+    }
+
+    fn is_dirty(&self) -> bool {
+        let config = self.disk.iter().find(|(path, _)| *path == self.config.path);
+        self.state
+            .has_unsaved_changes(&self.saved_modes, config.and_then(|(_, text)| text.as_deref()))
+    }
+
+    // Backs up and writes swhkdrc. `Ok(false)` means it needs root and the
+    // privileged save banner takes over.
+    fn write_config(&mut self) -> Result<bool, String> {
+        let path = self.config.path.clone();
+        if discovery::needs_privilege(&path) {
+            self.ui.privileged_save = true;
+            return Ok(false);
+        }
+        self.state.backup_config(&path, self.settings.backup_count)?;
+        self.state.save_to_custom_path(&path.display().to_string())?;
+        Ok(true)
+    }
+
+    // Moves or copies rows of the selected mode into mode `to`. Rows already
+    // bound there stay put and are reported; the rest can be undone.
+    fn transfer_hotkeys(&mut self, rows: &[usize], to: usize, copy: bool) {
+        let before = self.state.modes.clone();
+        let from = self.state.selected_mode;
+        let skipped = match self.state.transfer_hotkeys(from, rows, to, copy) {
+            Ok(skipped) => skipped,
+            Err(e) => {
+                self.error = Some(e);
+                return;
+            }
+        };
+        let target = self.state.modes[to].name.clone();
+        let count = self.state.modes[to].hotkeys.len() - before[to].hotkeys.len();
+        if count > 0 {
+            self.push_undo(format!("{} {count} hotkey(s) to {target}", if copy { "copy" } else { "move" }), before);
+        }
+        self.forget_rows();
+        self.error = if skipped.is_empty() {
+            None
+        } else {
+            Some(format!("Already bound in {target}, left in place: {}", skipped.join(", ")))
+        };
+        let _ = self.save_state();
+    }
+
+    // Applies a bulk edit to the selected rows of the selected mode.
+    fn edit_selected(&mut self, label: &str, edit: impl FnOnce(&mut AppState, usize, &[usize])) {
+        let rows: Vec<usize> = self.ui.selected_rows.iter().copied().collect();
+        if rows.is_empty() {
+            return;
+        }
+        let before = self.state.modes.clone();
+        let mode = self.state.selected_mode;
+        edit(&mut self.state, mode, &rows);
+        if self.state.modes != before {
+            self.push_undo(format!("{label} {} hotkey(s)", rows.len()), before);
+            let _ = self.save_state();
+        }
+    }
+
+    // Call right after the edit, with the modes as they were before it.
+    fn push_undo(&mut self, label: String, before: Vec<data_model::AppMode>) {
+        let selected_mode = before.get(self.state.selected_mode).map_or(0, |m| m.id);
+        let after = self.state.modes.clone();
+        self.ui.undo.push(UndoStep { label, before, after, selected_mode });
+        if self.ui.undo.len() > UNDO_LIMIT {
+            self.ui.undo.remove(0);
+        }
+    }
+
+    // Row indices shift when the mode's rows are replaced wholesale.
+    fn forget_rows(&mut self) {
+        self.state.recording_hotkey = None;
+        self.ui.tag_drafts.clear();
+        self.ui.expanded_rows.clear();
+        self.ui.selected_rows.clear();
+        self.ui.selection_anchor = None;
+        self.ui.snippets = None;
+    }
+
+    // The bodies of the destructive actions, run once they are confirmed.
+    // Deletions can be undone.
+    fn delete_mode(&mut self, idx: usize) {
+        let before = self.state.modes.clone();
+        if let Some(removed) = self.state.delete_mode(idx) {
+            self.push_undo(format!("delete mode {}", removed.name), before);
+            self.ui.map_scope = None;
+            self.forget_rows();
+            let _ = self.save_state();
+        }
+    }
+
+    fn delete_hotkey(&mut self, idx: usize) {
+        let before = self.state.modes.clone();
+        let mode = &mut self.state.modes[self.state.selected_mode];
+        if idx < mode.hotkeys.len() {
+            let removed = mode.hotkeys.remove(idx);
+            self.push_undo(format!("delete {}", removed.combo_string()), before);
+            self.forget_rows();
+            let _ = self.save_state();
+        }
+    }
+
+    // The config's entry in `disk` if it is among the external changes.
+    // Included files are never written by the GUI, so a change to one of them
+    // only needs acknowledging; swhkd reads it on its next reload.
+    fn changed_config(&self) -> Option<(std::path::PathBuf, Option<String>)> {
+        self.disk
+            .iter()
+            .find(|(path, _)| *path == self.config.path && self.ui.external_changes.contains(path))
+            .cloned()
+    }
+
+    fn reload_from_disk(&mut self) {
+        let Some((config, _)) = self.changed_config() else {
+            self.sync_disk_snapshot();
+            return;
+        };
+        let mut temp_state = AppState::default();
+        match temp_state.load_from_swhkd_config_at(&config.display().to_string()) {
+            Ok(_) => {
+                self.state.modes = temp_state.modes;
+                self.state.includes = temp_state.includes;
+                self.state.selected_mode = 0;
+                self.forget_rows();
+                self.ui.undo.clear();
+                let _ = self.save_state();
+                self.sync_disk_snapshot();
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("Failed to reload {}: {e}", config.display())),
+        }
+    }
+
+    fn switch_profile(&mut self, name: String) -> Command<Message> {
+        let dir = self.profiles_dir();
+        if let Some(current) = &self.ui.active_profile {
+            if let Err(e) = profiles::save(&dir, current, &self.state) {
+                self.error = Some(format!("Could not save profile {current}: {e}"));
+                return Command::none();
+            }
+        }
+        match profiles::load(&dir, &name) {
+            Ok(profile) => {
+                self.state.modes = profile.modes;
+                self.state.selected_mode = profile.selected_mode;
+                self.state.ensure_mode();
+                self.forget_rows();
+                self.ui.undo.clear();
+                self.ui.map_scope = None;
+                self.ui.active_profile = Some(name);
+                self.error = None;
+                let _ = self.save_state();
+                // Switching is applied like any other edit: diff first,
+                // then write and reload.
+                return self.check_config(AfterCheck::Preview);
+            }
+            Err(e) => self.error = Some(e),
+        }
+        Command::none()
+    }
+
+    fn profiles_dir(&self) -> std::path::PathBuf {
+        profiles::profiles_dir(&self.settings.state_path)
+    }
+
+    fn dialog_dir(&self) -> std::path::PathBuf {
+        self.settings.dialog_dir.clone()
+    }
+
+    // Modes from swhkdrc text; a missing file parses as a single empty mode.
+    fn parse_config(text: Option<&String>) -> AppState {
+        let mut parsed = AppState::default();
+        if let Some(text) = text {
+            parsed.load_from_swhkd_config_str(text);
+        }
+        parsed
+    }
 }
 
 
@@ -25,30 +368,176 @@ impl Application for SwhkdGui {
     type Executor = iced::executor::Default;
     type Message = Message;
     type Theme = Theme;
-    type Flags = ();
+    type Flags = Startup;
 
 
-    fn new(_flags: ()) -> (Self, Command<Self::Message>) {
-    // Try load saved GUI state from JSON file
-    let state = AppState::load_from_json_file("swhkd_gui_saved.json");
-    (Self { state, error: None }, Command::none())
+    fn new(flags: Startup) -> (Self, Command<Self::Message>) {
+    let settings = flags.overrides.apply(&flags.stored_settings);
+    // Try load saved GUI state from JSON file, picking up the state an older
+    // version left in the working directory
+    let state_path = if !settings.state_path.exists() && std::path::Path::new(settings::LEGACY_STATE_PATH).exists() {
+        settings::LEGACY_STATE_PATH.to_string()
+    } else {
+        settings.state_path.display().to_string()
+    };
+    let first_run = !std::path::Path::new(&state_path).exists();
+    let mut errors: Vec<String> = flags.settings_error.into_iter().collect();
+    let mut state = match AppState::load_from_json_file(&state_path) {
+        Ok(state) => state,
+        Err(e) => {
+            // Keep the unreadable file instead of overwriting it on the next save.
+            let kept = match AppState::set_aside_state_file(&state_path) {
+                Ok(aside) => format!("the old file was moved to {}", aside.display()),
+                Err(move_error) => move_error,
+            };
+            errors.push(format!("{e}\nStarting with an empty state; {kept}."));
+            AppState::default()
+        }
+    };
+    let config = discovery::locate(&flags.stored_settings, &flags.overrides);
+    // On the very first run start from the config swhkd is already using.
+    // Later runs only take its `include` lines, which the GUI doesn't edit.
+    if config.path.is_file() {
+        let mut loaded = AppState::default();
+        if loaded.load_from_swhkd_config_at(&config.path.display().to_string()).is_ok() {
+            if first_run {
+                state = loaded;
+            } else {
+                state.includes = loaded.includes;
+            }
+        }
+    }
+    let ui = ViewState {
+        config_location: Some(config.clone()),
+        settings_form: SettingsForm::from_settings(&flags.stored_settings),
+        settings_overridden: !flags.overrides.is_empty(),
+        ..ViewState::default()
+    };
+    let mut gui = Self {
+        state,
+        error: if errors.is_empty() { None } else { Some(errors.join("\n")) },
+        ui,
+        last_saved: None,
+        disk: Vec::new(),
+        stored_settings: flags.stored_settings,
+        overrides: flags.overrides,
+        settings,
+        config,
+        saved_modes: Vec::new(),
+        quit_after_reload: false,
+        dirty: false,
+    };
+    gui.saved_modes = gui.state.modes.clone();
+    if first_run {
+        let _ = gui.save_state();
+    }
+    gui.ui.profiles = profiles::list(&gui.profiles_dir());
+    gui.sync_disk_snapshot();
+    gui.dirty = gui.is_dirty();
+    let refresh = gui.refresh_daemon_status();
+    (gui, refresh)
 }
 
 
 
     fn title(&self) -> String {
-        "SWHKD GUI Configurator".to_string()
+        if self.dirty {
+            "SWHKD GUI Configurator (modified)".to_string()
+        } else {
+            "SWHKD GUI Configurator".to_string()
+        }
     }
 
 
 
 
 fn update(&mut self, message: Message) -> Command<Message> {
+    // Re-serialising the config to tell whether it is unsaved is too costly
+    // for every redraw, so it is worked out after each message instead,
+    // skipping the frequent ones that don't touch the modes or the disk.
+    let recheck = !matches!(
+        message,
+        Message::ModifiersChanged(_)
+            | Message::RefreshDaemonStatus
+            | Message::DaemonStatusRefreshed(_)
+            | Message::EditSearch(_)
+            | Message::FilterTag(_)
+            | Message::SortBy(_)
+            | Message::FilterRows(_)
+            | Message::ToggleDiffLayout
+    );
+    let command = self.handle(message);
+    if recheck {
+        self.dirty = self.is_dirty();
+    }
+    command
+}
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        // Held modifiers feed the keyboard map and shift/ctrl-click selection.
+        let modifiers = iced::subscription::events_with(|event, _status| match event {
+            iced::Event::Keyboard(Event::ModifiersChanged(modifiers)) => Some(Message::ModifiersChanged(modifiers)),
+            _ => None,
+        });
+
+        let recorder = if self.state.recording_hotkey.is_some() {
+            iced::subscription::events_with(|event, _status| {
+                if let iced::Event::Keyboard(Event::KeyPressed { key_code, modifiers, .. }) = event {
+                    let mut parts = Vec::new();
+                    if modifiers.control() { parts.push("ctrl".to_string()); }
+                    if modifiers.alt() { parts.push("alt".to_string()); }
+                    if modifiers.shift() { parts.push("shift".to_string()); }
+                    if modifiers.logo() { parts.push("super".to_string()); }
+
+
+                    
+                    // Looked up by name so the table in `keyboard_map` can be
+                    // tested without iced.
+                    match keyboard_map::recorded_key(&format!("{key_code:?}")) {
+                        Some(key) => parts.push(key.to_string()),
+                        None => return Some(Message::ClearError),
+                    }
+
+
+                    if parts.is_empty() {
+                        Some(Message::ClearError)
+                    } else {
+                        Some(Message::KeyRecorded(parts.join(" + ")))
+                    }
+                } else {
+                    None
+                }
+            })
+        } else {
+            Subscription::none()
+        };
+
+        let status = iced::time::every(Duration::from_secs(2)).map(|_| Message::RefreshDaemonStatus);
+
+        let config_watch = watcher::watch(self.disk.iter().map(|(path, _)| path.clone()).collect());
+
+        let close = iced::subscription::events_with(|event, _status| match event {
+            iced::Event::Window(iced::window::Event::CloseRequested) => Some(Message::CloseRequested),
+            _ => None,
+        });
+
+        Subscription::batch(vec![recorder, modifiers, status, config_watch, close])
+    }
+
+
+    fn view(&self) -> Element<Self::Message> {
+        view(&self.state, &self.error, &self.ui, &self.saved_modes)
+    }
+}
+
+impl SwhkdGui {
+fn handle(&mut self, message: Message) -> Command<Message> {
     use Message::*;
     match message {
         SelectMode(idx) => {
             if idx < self.state.modes.len() {
                 self.state.selected_mode = idx;
+                self.forget_rows();
             }
         }
         EditModeName(new_name) => {
@@ -66,35 +555,345 @@ fn update(&mut self, message: Message) -> Command<Message> {
                 hk.action.active = active;
             }
         }
-        DeleteHotkey(idx) => {
-    if let Some(mode) = self.state.modes.get_mut(self.state.selected_mode) {
-        if idx < mode.hotkeys.len() {
-            mode.hotkeys.remove(idx);
-            
-            let _ = self.state.save_to_json_file("swhkd_gui_saved.json");
+        EditDescription(idx, description) => {
+            if let Some(hk) = self.state.modes[self.state.selected_mode].hotkeys.get_mut(idx) {
+                hk.action.description = if description.is_empty() { None } else { Some(description) };
+            }
         }
-    }
-}
+        ToggleEnvironment(idx) => {
+            if !self.ui.expanded_rows.remove(&idx) {
+                self.ui.expanded_rows.insert(idx);
+            }
+        }
+        EditWorkingDir(idx, dir) => {
+            if let Some(hk) = self.state.modes[self.state.selected_mode].hotkeys.get_mut(idx) {
+                hk.action.working_dir = if dir.is_empty() { None } else { Some(dir) };
+            }
+        }
+        AddEnvVar(idx) => {
+            if let Some(hk) = self.state.modes[self.state.selected_mode].hotkeys.get_mut(idx) {
+                hk.action.env.push(EnvVar::default());
+            }
+        }
+        EditEnvName(idx, var, name) => {
+            let hotkeys = &mut self.state.modes[self.state.selected_mode].hotkeys;
+            if let Some(env) = hotkeys.get_mut(idx).and_then(|hk| hk.action.env.get_mut(var)) {
+                env.name = name;
+            }
+        }
+        EditEnvValue(idx, var, value) => {
+            let hotkeys = &mut self.state.modes[self.state.selected_mode].hotkeys;
+            if let Some(env) = hotkeys.get_mut(idx).and_then(|hk| hk.action.env.get_mut(var)) {
+                env.value = value;
+            }
+        }
+        RemoveEnvVar(idx, var) => {
+            if let Some(hk) = self.state.modes[self.state.selected_mode].hotkeys.get_mut(idx) {
+                if var < hk.action.env.len() {
+                    hk.action.env.remove(var);
+                }
+            }
+        }
+        EditTagDraft(idx, draft) => {
+            self.ui.tag_drafts.insert(idx, draft);
+        }
+        AddTag(idx) => {
+            let draft = self.ui.tag_drafts.remove(&idx).unwrap_or_default();
+            if let Some(hk) = self.state.modes[self.state.selected_mode].hotkeys.get_mut(idx) {
+                for tag in data_model::parse_tags(&draft) {
+                    if !hk.action.tags.contains(&tag) {
+                        hk.action.tags.push(tag);
+                    }
+                }
+            }
+        }
+        RemoveTag(idx, tag) => {
+            if let Some(hk) = self.state.modes[self.state.selected_mode].hotkeys.get_mut(idx) {
+                hk.action.tags.retain(|t| *t != tag);
+            }
+        }
+        EditSearch(query) => {
+            self.ui.table.search = query;
+            self.ui.table.retain_visible(&self.state, &mut self.ui.selected_rows, &mut self.ui.selection_anchor);
+        }
+        FilterTag(tag) => {
+            self.ui.table.tag_filter = tag;
+            self.ui.table.retain_visible(&self.state, &mut self.ui.selected_rows, &mut self.ui.selection_anchor);
+        }
+        SortBy(column) => self.ui.table.toggle_sort(column),
+        FilterRows(filter) => {
+            self.ui.table.filter = filter;
+            self.ui.table.retain_visible(&self.state, &mut self.ui.selected_rows, &mut self.ui.selection_anchor);
+        }
+        ToggleKeyboardMap => {
+            self.ui.show_keyboard_map = !self.ui.show_keyboard_map;
+            self.ui.held_modifiers.clear();
+        }
+        SelectKeyboardLayout(layout) => self.ui.keyboard_layout = layout,
+        SelectMapScope(scope) => {
+            self.ui.map_scope = match scope {
+                MapScope::AllModes => None,
+                MapScope::Mode(idx, _) => Some(idx),
+            };
+        }
+        ToggleMapModifier(modifier) => {
+            if !self.ui.map_modifiers.remove(&modifier) {
+                self.ui.map_modifiers.insert(modifier);
+            }
+        }
+        ModifiersChanged(modifiers) => {
+            let held = &mut self.ui.held_modifiers;
+            held.clear();
+            if modifiers.control() { held.insert("ctrl".to_string()); }
+            if modifiers.alt() { held.insert("alt".to_string()); }
+            if modifiers.shift() { held.insert("shift".to_string()); }
+            if modifiers.logo() { held.insert("super".to_string()); }
+        }
+        KeyMapClicked(key) => {
+            // New hotkeys go into the mode the map is showing, or the selected
+            // one when it shows every mode.
+            let mode_idx = self.ui.map_scope.unwrap_or(self.state.selected_mode);
+            if let Some(mode) = self.state.modes.get_mut(mode_idx) {
+                mode.hotkeys.push(data_model::GuiHotkey::new(
+                    self.ui.active_map_modifiers(),
+                    key,
+                    data_model::GuiAction::default(),
+                ));
+                self.state.selected_mode = mode_idx;
+                self.ui.show_keyboard_map = false;
+                self.forget_rows();
+            }
+        }
+        ReorderHotkey(from, to) => {
+            self.state.reorder_hotkey(self.state.selected_mode, from, to);
+            self.forget_rows();
+        }
+        ReorderMode(from, to) => {
+            self.state.reorder_mode(from, to);
+            self.ui.map_scope = None;
+        }
+        MoveHotkey(idx, to) => self.transfer_hotkeys(&[idx], to, false),
+        CopyHotkey(idx, to) => self.transfer_hotkeys(&[idx], to, true),
+        RowClicked(idx) => {
+            let held = &self.ui.held_modifiers;
+            let (shift, ctrl) = (held.contains("shift"), held.contains("ctrl"));
+            let visible = self.ui.table.visible_rows(&self.state);
+            let range = self.ui.selection_anchor.filter(|_| shift).and_then(|anchor| {
+                let a = visible.iter().position(|&i| i == anchor)?;
+                let b = visible.iter().position(|&i| i == idx)?;
+                Some(visible[a.min(b)..=a.max(b)].to_vec())
+            });
+            let selected = &mut self.ui.selected_rows;
+            match range {
+                // Shift-click selects everything shown between the anchor and
+                // this row, on top of the selection when ctrl is held too.
+                Some(range) => {
+                    if !ctrl {
+                        selected.clear();
+                    }
+                    selected.extend(range);
+                }
+                None if ctrl => {
+                    if !selected.remove(&idx) {
+                        selected.insert(idx);
+                    }
+                    self.ui.selection_anchor = Some(idx);
+                }
+                None => {
+                    let only_this = selected.len() == 1 && selected.contains(&idx);
+                    selected.clear();
+                    if !only_this {
+                        selected.insert(idx);
+                    }
+                    self.ui.selection_anchor = Some(idx);
+                }
+            }
+        }
+        SelectAllRows => {
+            self.ui.selected_rows = self.ui.table.visible_rows(&self.state).into_iter().collect();
+        }
+        ClearSelection => {
+            self.ui.selected_rows.clear();
+            self.ui.selection_anchor = None;
+        }
+        ActivateSelected => self.edit_selected("activate", |state, mode, rows| state.set_active(mode, rows, true)),
+        DeactivateSelected => self.edit_selected("deactivate", |state, mode, rows| state.set_active(mode, rows, false)),
+        DeleteSelected => {
+            if !self.ui.selected_rows.is_empty() {
+                self.ui.confirmation = Some(Confirmation::DeleteSelected);
+            }
+        }
+        MoveSelected(to) => {
+            let rows: Vec<usize> = self.ui.selected_rows.iter().copied().collect();
+            self.transfer_hotkeys(&rows, to, false);
+        }
+        EditBulkTag(draft) => self.ui.bulk_tag_draft = draft,
+        TagSelected => {
+            let tags = data_model::parse_tags(&std::mem::take(&mut self.ui.bulk_tag_draft));
+            self.edit_selected("tag", |state, mode, rows| state.add_tags(mode, rows, &tags));
+        }
+        UntagSelected(tag) => self.edit_selected("untag", |state, mode, rows| state.remove_tag(mode, rows, &tag)),
+        Undo => {
+            if let Some(step) = self.ui.undo.pop() {
+                undo::revert(&mut self.state, &step.before, &step.after);
+                if let Some(idx) = self.state.modes.iter().position(|m| m.id == step.selected_mode) {
+                    self.state.selected_mode = idx;
+                }
+                self.forget_rows();
+                self.error = None;
+                let _ = self.save_state();
+            }
+        }
+        // Rows without a key or command are deleted without asking.
+        DeleteHotkey(idx) => match self.state.modes[self.state.selected_mode].hotkeys.get(idx) {
+            Some(hk) if hk.key.is_empty() && hk.action.command.trim().is_empty() => self.delete_hotkey(idx),
+            Some(_) => self.ui.confirmation = Some(Confirmation::DeleteHotkey(idx)),
+            None => {}
+        },
+        RunCommand(idx) => {
+            if self.ui.run.as_ref().is_some_and(|run| run.output.is_none()) {
+                self.error = Some("A command is still running".to_string());
+                return Command::none();
+            }
+            let Some(hk) = self.state.modes[self.state.selected_mode].hotkeys.get(idx) else {
+                return Command::none();
+            };
+            if hk.action.command.trim().is_empty() {
+                self.error = Some("No command to run".to_string());
+                return Command::none();
+            }
+            let command = hk.action.shell_command().trim().to_string();
+            let cancel = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+            self.ui.run = Some(RunPanel { command: command.clone(), cancel: cancel.clone(), output: None });
+            return Command::perform(
+                async move {
+                    let shown = command.clone();
+                    tokio::task::spawn_blocking(move || runner::run(&command, runner::RUN_TIMEOUT, &cancel))
+                        .await
+                        .unwrap_or_else(|e| runner::RunOutput {
+                            command: shown,
+                            stdout: String::new(),
+                            stderr: String::new(),
+                            status: runner::RunStatus::Failed(e.to_string()),
+                        })
+                },
+                Message::RunFinished,
+            );
+        }
+        RunFinished(output) => {
+            if let Some(run) = &mut self.ui.run {
+                run.output = Some(output);
+            }
+        }
+        KillRun => {
+            if let Some(run) = &self.ui.run {
+                run.cancel.store(true, std::sync::atomic::Ordering::Relaxed);
+            }
+        }
+        CloseRunOutput => {
+            if let Some(run) = self.ui.run.take() {
+                run.cancel.store(true, std::sync::atomic::Ordering::Relaxed);
+            }
+        }
+        OpenSnippets(idx) => {
+            // Read the user file again so edits to it show up without a restart.
+            let (library, load_error) = snippets::library(&snippets::user_snippets_file());
+            self.ui.snippets = Some(SnippetPicker { row: idx, library, load_error, ..SnippetPicker::default() });
+        }
+        EditSnippetSearch(search) => {
+            if let Some(picker) = &mut self.ui.snippets {
+                picker.search = search;
+            }
+        }
+        ChooseSnippet(idx) => {
+            if let Some(picker) = &mut self.ui.snippets {
+                picker.chosen = Some(idx);
+                picker.values.clear();
+            }
+        }
+        EditSnippetValue(name, value) => {
+            if let Some(picker) = &mut self.ui.snippets {
+                picker.values.insert(name, value);
+            }
+        }
+        InsertSnippet(append) => {
+            let Some(picker) = self.ui.snippets.take() else {
+                return Command::none();
+            };
+            let Some(snippet) = picker.chosen_snippet() else {
+                return Command::none();
+            };
+            let filled = snippet.fill(&picker.values);
+            if let Some(hk) = self.state.modes[self.state.selected_mode].hotkeys.get_mut(picker.row) {
+                hk.action.command = match hk.action.command.trim_end() {
+                    current if append && !current.is_empty() => format!("{current} {filled}"),
+                    _ => filled,
+                };
+            }
+        }
+        CloseSnippets => self.ui.snippets = None,
+        Confirm => {
+            match self.ui.confirmation.take() {
+                Some(Confirmation::DeleteMode(idx)) => self.delete_mode(idx),
+                Some(Confirmation::DeleteHotkey(idx)) => self.delete_hotkey(idx),
+                Some(Confirmation::DeleteSelected) => {
+                    self.edit_selected("delete", |state, mode, rows| state.delete_hotkeys(mode, rows));
+                    self.forget_rows();
+                }
+                Some(Confirmation::ReloadFromDisk) => self.reload_from_disk(),
+                Some(Confirmation::SwitchProfile(name)) => return self.switch_profile(name),
+                None => {}
+            }
+        }
+        CancelConfirmation => self.ui.confirmation = None,
 
         AddHotkey => {
             let app = &mut self.state.modes[self.state.selected_mode];
-            app.hotkeys.push(crate::data_model::GuiHotkey {
-                modifiers: Default::default(),
-                key: String::new(),
-                action: crate::data_model::GuiAction {
-                    command: String::new(),
-                    active: true,
-                    layer_id: 0,
-                },
-            });
+            app.hotkeys.push(crate::data_model::GuiHotkey::new(
+                Default::default(),
+                String::new(),
+                crate::data_model::GuiAction::default(),
+            ));
             self.state.recording_hotkey = Some(app.hotkeys.len() - 1);
         }
+        SwitchProfile(name) => {
+            if self.ui.active_profile.is_some() {
+                return self.switch_profile(name);
+            } else {
+                self.ui.confirmation = Some(Confirmation::SwitchProfile(name));
+            }
+        }
+        EditProfileName(name) => self.ui.profile_draft = name,
+        SaveProfile => {
+            let name = match self.ui.profile_draft.trim() {
+                "" => self.ui.active_profile.clone().unwrap_or_default(),
+                draft => draft.to_string(),
+            };
+            let dir = self.profiles_dir();
+            match profiles::save(&dir, &name, &self.state) {
+                Ok(()) => {
+                    self.ui.profiles = profiles::list(&dir);
+                    self.ui.active_profile = Some(name.trim().to_string());
+                    self.ui.profile_draft.clear();
+                    self.error = None;
+                }
+                Err(e) => self.error = Some(e),
+            }
+        }
         AddMode => {
-            self.state.modes.push(crate::data_model::AppMode {
-                name: "New Mode".to_string(),
-                hotkeys: vec![],
-            });
-            self.state.selected_mode = self.state.modes.len() - 1;
+            self.state.selected_mode = self.state.add_mode("New Mode", Vec::new());
+            self.forget_rows();
+        }
+        AddModeFromTemplate(template) => {
+            self.state.selected_mode = self.state.add_mode(template.name, template.hotkeys());
+            self.forget_rows();
+        }
+        DuplicateMode(idx) => {
+            if let Some(copy) = self.state.duplicate_mode(idx) {
+                self.state.selected_mode = copy;
+                self.ui.map_scope = None;
+                self.forget_rows();
+            }
         }
         StartRecording(idx) => {
             self.state.recording_hotkey = Some(idx);
@@ -119,12 +918,164 @@ fn update(&mut self, message: Message) -> Command<Message> {
         StopRecording => {
             self.state.recording_hotkey = None;
         }
-        SaveConfig => {
-            match self.state.save_to_swhkd_config() {
-                Ok(_) => self.error = None,
+        SaveConfig => return self.check_config(AfterCheck::Preview),
+        ConfigChecked(then, text, result) => {
+            self.ui.checking_config = false;
+            // Edited while the check ran: check what would be written now.
+            if text != self.state.to_swhkd_config_text() {
+                return self.check_config(then);
+            }
+            if let Err(e) = result {
+                self.error = Some(e);
+                return Command::none();
+            }
+            match then {
+                AfterCheck::Preview => self.ui.save_preview = Some(self.save_preview()),
+                AfterCheck::SaveAndQuit => match self.write_config() {
+                    Ok(true) => {
+                        self.quit_after_reload = true;
+                        return self.config_saved(&text);
+                    }
+                    Ok(false) => self.error = Some("The config needs root rights; save it, then close the window again".to_string()),
+                    Err(e) => self.error = Some(e),
+                },
+                AfterCheck::SaveAs(path) => match self.state.save_to_custom_path(&path) {
+                    Ok(_) => self.error = None,
+                    Err(e) => self.error = Some(e),
+                },
+            }
+        }
+        ToggleDiffLayout => self.ui.side_by_side_diff = !self.ui.side_by_side_diff,
+        CancelSave => self.ui.save_preview = None,
+        ConfirmSave => {
+            let Some(preview) = self.ui.save_preview.take() else {
+                return Command::none();
+            };
+            // Edited while the preview was open: check and show the new
+            // text instead.
+            if preview.text != self.state.to_swhkd_config_text() || preview.path != self.config.path {
+                return self.check_config(AfterCheck::Preview);
+            }
+            match self.write_config() {
+                Ok(true) => return self.config_saved(&preview.text),
+                Ok(false) => {}
+                Err(e) => self.error = Some(e),
+            }
+        }
+        // pkexec waits for the polkit dialog, so it runs off the UI thread.
+        SavePrivileged => {
+            if let Err(e) = self.state.check_duplicate_bindings() {
+                self.ui.privileged_save = false;
+                self.error = Some(e);
+                return Command::none();
+            }
+            let path = self.config.path.clone();
+            let text = self.state.to_swhkd_config_text();
+            self.ui.privileged_save = false;
+            self.ui.saving_privileged = true;
+            return Command::perform(
+                async move {
+                    let written = text.clone();
+                    let result = tokio::task::spawn_blocking(move || discovery::write_privileged(&path, &text))
+                        .await
+                        .unwrap_or_else(|e| Err(format!("pkexec failed: {e}")));
+                    (written, result)
+                },
+                |(text, result)| Message::PrivilegedSaveFinished(text, result),
+            );
+        }
+        PrivilegedSaveFinished(text, result) => {
+            self.ui.saving_privileged = false;
+            match result {
+                Ok(()) => return self.config_saved(&text),
+                Err(e) => self.error = Some(e),
+            }
+        }
+        CancelPrivilegedSave => self.ui.privileged_save = false,
+        CloseRequested => {
+            if !self.is_dirty() {
+                return iced::window::close();
+            }
+            self.ui.confirm_close = true;
+        }
+        // Writes swhkdrc as well as the state, so swhkd isn't left with
+        // older bindings than the GUI shows next time.
+        SaveAndQuit => {
+            self.ui.confirm_close = false;
+            return self.check_config(AfterCheck::SaveAndQuit);
+        }
+        DiscardAndQuit => return iced::window::close(),
+        CancelQuit => self.ui.confirm_close = false,
+        ReloadFinished(report) => {
+            // A failed reload or state save keeps the window open to show why.
+            if std::mem::take(&mut self.quit_after_reload) && report.success && self.error.is_none() {
+                return iced::window::close();
+            }
+            self.ui.reload_report = Some(report);
+        }
+        RefreshDaemonStatus => return self.refresh_daemon_status(),
+        DaemonStatusRefreshed(status) => {
+            if status.is_some() {
+                self.ui.daemon_status = status;
+            }
+        }
+        ConfigChangedOnDisk => {
+            // Our own saves update the snapshot first, so they compare equal.
+            self.ui.external_changes = watcher::snapshot(&self.disk.iter().map(|(p, _)| p.clone()).collect::<Vec<_>>())
+                .into_iter()
+                .zip(&self.disk)
+                .filter(|(now, then)| now.1 != then.1)
+                .map(|(now, _)| now.0)
+                .collect();
+        }
+        ReloadFromDisk => self.ui.confirmation = Some(Confirmation::ReloadFromDisk),
+        KeepMine => self.sync_disk_snapshot(),
+        ToggleSettings => {
+            self.ui.show_settings = !self.ui.show_settings;
+            self.ui.settings_form = SettingsForm::from_settings(&self.stored_settings);
+        }
+        EditSettingsField(field, value) => self.ui.settings_form.edit(field, value),
+        SelectReloadMethod(method) => self.ui.settings_form.reload_method = method,
+        SaveSettings => {
+            let saved = self.ui.settings_form.to_settings().and_then(|settings| settings.save().map(|_| settings));
+            match saved {
+                Ok(settings) => {
+                    self.stored_settings = settings;
+                    self.settings = self.overrides.apply(&self.stored_settings);
+                    self.locate_config();
+                    self.ui.profiles = profiles::list(&self.profiles_dir());
+                    self.ui.show_settings = false;
+                    self.last_saved = None;
+                    self.sync_disk_snapshot();
+                    self.error = None;
+                    return self.refresh_daemon_status();
+                }
                 Err(e) => self.error = Some(e),
             }
         }
+        MergeWithDisk => {
+            let Some((config, before)) = self.changed_config() else {
+                self.sync_disk_snapshot();
+                return Command::none();
+            };
+            let now = std::fs::read_to_string(&config).ok();
+            let base = Self::parse_config(before.as_ref());
+            let theirs = Self::parse_config(now.as_ref());
+            let merged = merge::three_way(&base.modes, &self.state.modes, &theirs.modes);
+
+            self.state.modes = merged.modes;
+            self.state.includes = theirs.includes;
+            self.state.ensure_mode();
+            self.forget_rows();
+            self.ui.undo.clear();
+            let _ = self.save_state();
+            self.sync_disk_snapshot();
+            self.error = if merged.conflicts.is_empty() {
+                None
+            } else {
+                Some(format!("Merged with {} conflict(s):\n{}", merged.conflicts.len(), merged.conflicts.join("\n")))
+            };
+        }
         ShowError(msg) => self.error = Some(msg),
         ClearError => self.error = None,
 
@@ -133,13 +1084,14 @@ fn update(&mut self, message: Message) -> Command<Message> {
 
 
         OpenBinaryPicker(idx) => {
+            let dir = self.dialog_dir();
             return Command::perform(
                 async move {
                     FileDialog::new()
     .set_title("Select Config")
     
     .add_filter("All Files", &["*"])             
-    .set_directory("tests/sweet_samples")
+    .set_directory(dir)
     .pick_file()
 
 
@@ -158,13 +1110,14 @@ fn update(&mut self, message: Message) -> Command<Message> {
 
 
         LoadConfigFile => {
+    let dir = self.dialog_dir();
     return Command::perform(
-        async {
+        async move {
             FileDialog::new()
     .set_title("Select swhkdrc file")
     .add_filter("SWHKD Config", &["swhkdrc"]) 
     .add_filter("All Files", &["*"])          
-    .set_directory("tests/sweet_samples")     
+    .set_directory(dir)     
     .pick_file()
                 .map(|f| Message::ConfigFilePicked(Some(f.display().to_string())))
                 .unwrap_or(Message::ConfigFilePicked(None))
@@ -175,28 +1128,111 @@ fn update(&mut self, message: Message) -> Command<Message> {
 
 
         ConfigFilePicked(Some(path)) => {
-    let mut temp_state = crate::data_model::AppState::default();
-    if let Err(err) = temp_state.load_from_swhkd_config_at(&path) {
-        self.error = Some(format!("Failed to load: {err:?}"));
-    } else {
-        if let Some(new_hotkeys) = temp_state.modes.first().map(|m| m.hotkeys.clone()) {
-            if let Some(mode) = self.state.modes.get_mut(self.state.selected_mode) {
-                mode.hotkeys = new_hotkeys;
-                let _ = self.state.save_to_json_file("swhkd_gui_saved.json");
+            let mut imported = AppState::default();
+            match imported.load_from_swhkd_config_at(&path) {
+                Ok(()) => {
+                    let plan = ImportPlan::new(imported.modes, &self.state.modes);
+                    self.ui.import = Some(ImportWizard { path, plan });
+                    self.error = None;
+                }
+                Err(err) => self.error = Some(format!("Failed to load: {err:?}")),
             }
         }
-        self.state.recording_hotkey = None;
-        self.error = None;
-    }
-}
+        SetImportTarget(idx, target) => {
+            if let Some(target_slot) = self.ui.import.as_mut().and_then(|w| w.plan.targets.get_mut(idx)) {
+                *target_slot = target;
+            }
+        }
+        SetImportPolicy(policy) => {
+            if let Some(wizard) = &mut self.ui.import {
+                wizard.plan.policy = policy;
+            }
+        }
+        CancelImport => self.ui.import = None,
+        ConfirmImport => {
+            if let Some(wizard) = self.ui.import.take() {
+                let before = self.state.modes.clone();
+                self.state.modes = wizard.plan.apply(&self.state).modes;
+                let name = std::path::Path::new(&wizard.path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or(wizard.path);
+                self.push_undo(format!("import {name}"), before);
+                self.forget_rows();
+                self.error = None;
+                let _ = self.save_state();
+            }
+        }
+        ConfigFilePicked(None) => {}
 
 
-        ConfigFilePicked(None) => {}
+        ImportShortcutsFile => {
+            let dir = self.dialog_dir();
+            return Command::perform(
+                async move {
+                    FileDialog::new()
+                        .set_title("Select GNOME or KDE shortcut export")
+                        .set_directory(dir)
+                        .add_filter("Shortcut exports", &["ini", "conf", "txt", "khotkeys"])
+                        .add_filter("All Files", &["*"])
+                        .pick_file()
+                        .map(|f| Message::ShortcutsFilePicked(Some(f.display().to_string())))
+                        .unwrap_or(Message::ShortcutsFilePicked(None))
+                },
+                |msg| msg,
+            );
+        }
+        ShortcutsFilePicked(Some(path)) => {
+            match importers::import_shortcuts_file(&path) {
+                Ok(imported) => {
+                    // Into the selected mode, through the same conflict check
+                    // and preview as swhkdrc imports.
+                    let Some(mode) = self.state.modes.get(self.state.selected_mode) else {
+                        return Command::none();
+                    };
+                    let mut plan = ImportPlan::new(vec![data_model::AppMode::new(mode.name.clone(), imported)], &self.state.modes);
+                    plan.targets = vec![config_import::ImportTarget::Existing(self.state.selected_mode)];
+                    self.ui.import = Some(ImportWizard { path, plan });
+                    self.error = None;
+                }
+                Err(e) => self.error = Some(format!("Failed to import shortcuts: {e}")),
+            }
+        }
+        ShortcutsFilePicked(None) => {}
+        ExportCheatSheet => {
+            let dir = self.dialog_dir();
+            return Command::perform(
+                async move {
+                    FileDialog::new()
+                        .set_title("Export cheat sheet")
+                        .set_directory(dir)
+                        .set_file_name("swhkd-cheatsheet.md")
+                        .add_filter("Markdown", &["md"])
+                        .add_filter("HTML", &["html"])
+                        .add_filter("SVG", &["svg"])
+                        .save_file()
+                        .map(|f| Message::CheatSheetSavePath(Some(f.display().to_string())))
+                        .unwrap_or(Message::CheatSheetSavePath(None))
+                },
+                |msg| msg,
+            );
+        }
+        CheatSheetSavePath(Some(path)) => {
+            let path = std::path::PathBuf::from(path);
+            let format = CheatSheetFormat::from_path(&path).unwrap_or(CheatSheetFormat::Markdown);
+            match cheatsheet::export(&self.state, &path, format) {
+                Ok(_) => self.error = None,
+                Err(e) => self.error = Some(format!("Failed to export cheat sheet: {e}")),
+            }
+        }
+        CheatSheetSavePath(None) => {}
         SaveConfigAs => {
+            let dir = self.dialog_dir();
             return Command::perform(
-                async {
+                async move {
                     FileDialog::new()
                         .set_file_name("swhkdrc")
+                        .set_directory(dir)
                         .save_file()
                         .map(|f| Message::ConfigFileSavePath(Some(f.display().to_string())))
                         .unwrap_or(Message::ConfigFileSavePath(None))
@@ -204,117 +1240,19 @@ fn update(&mut self, message: Message) -> Command<Message> {
                 |msg| msg,
             );
         }
-        ConfigFileSavePath(Some(path)) => {
-            match self.state.save_to_custom_path(&path) {
-                Ok(_) => self.error = None,
-                Err(e) => self.error = Some(e),
-            }
-        }
+        ConfigFileSavePath(Some(path)) => return self.check_config(AfterCheck::SaveAs(path)),
         ConfigFileSavePath(None) => {}
 
 
         
-        DeleteMode(idx) => {
-            if idx < self.state.modes.len() {
-                self.state.modes.remove(idx);
-                if self.state.selected_mode >= self.state.modes.len() && !self.state.modes.is_empty() {
-                    self.state.selected_mode = self.state.modes.len() - 1;
-                } else if self.state.modes.is_empty() {
-                    self.state.selected_mode = 0;
-                }
-            }
-        }
+        // Empty modes are deleted without asking.
+        DeleteMode(idx) => match self.state.modes.get(idx) {
+            Some(mode) if mode.hotkeys.is_empty() => self.delete_mode(idx),
+            Some(_) => self.ui.confirmation = Some(Confirmation::DeleteMode(idx)),
+            None => {}
+        },
     }
     Command::none()
 }
-
-
-
-    fn subscription(&self) -> Subscription<Self::Message> {
-        if self.state.recording_hotkey.is_some() {
-            iced::subscription::events_with(|event, _status| {
-                if let iced::Event::Keyboard(Event::KeyPressed { key_code, modifiers, .. }) = event {
-                    let mut parts = Vec::new();
-                    if modifiers.control() { parts.push("ctrl".to_string()); }
-                    if modifiers.alt() { parts.push("alt".to_string()); }
-                    if modifiers.shift() { parts.push("shift".to_string()); }
-                    if modifiers.logo() { parts.push("super".to_string()); }
-
-
-                    
-                    match key_code {
-                        KeyCode::A => parts.push("a".to_string()),
-                        KeyCode::B => parts.push("b".to_string()),
-                        KeyCode::C => parts.push("c".to_string()),
-                        KeyCode::D => parts.push("d".to_string()),
-                        KeyCode::E => parts.push("e".to_string()),
-                        KeyCode::F => parts.push("f".to_string()),
-                        KeyCode::G => parts.push("g".to_string()),
-                        KeyCode::H => parts.push("h".to_string()),
-                        KeyCode::I => parts.push("i".to_string()),
-                        KeyCode::J => parts.push("j".to_string()),
-                        KeyCode::K => parts.push("k".to_string()),
-                        KeyCode::L => parts.push("l".to_string()),
-                        KeyCode::M => parts.push("m".to_string()),
-                        KeyCode::N => parts.push("n".to_string()),
-                        KeyCode::O => parts.push("o".to_string()),
-                        KeyCode::P => parts.push("p".to_string()),
-                        KeyCode::Q => parts.push("q".to_string()),
-                        KeyCode::R => parts.push("r".to_string()),
-                        KeyCode::S => parts.push("s".to_string()),
-                        KeyCode::T => parts.push("t".to_string()),
-                        KeyCode::U => parts.push("u".to_string()),
-                        KeyCode::V => parts.push("v".to_string()),
-                        KeyCode::W => parts.push("w".to_string()),
-                        KeyCode::X => parts.push("x".to_string()),
-                        KeyCode::Y => parts.push("y".to_string()),
-                        KeyCode::Z => parts.push("z".to_string()),
-                        KeyCode::Key1 => parts.push("1".to_string()),
-                        KeyCode::Key2 => parts.push("2".to_string()),
-                        KeyCode::Key3 => parts.push("3".to_string()),
-                        KeyCode::Key4 => parts.push("4".to_string()),
-                        KeyCode::Key5 => parts.push("5".to_string()),
-                        KeyCode::Key6 => parts.push("6".to_string()),
-                        KeyCode::Key7 => parts.push("7".to_string()),
-                        KeyCode::Key8 => parts.push("8".to_string()),
-                        KeyCode::Key9 => parts.push("9".to_string()),
-                        KeyCode::Key0 => parts.push("0".to_string()),
-                        KeyCode::Escape => parts.push("escape".to_string()),
-                        KeyCode::Enter => parts.push("enter".to_string()),
-                        KeyCode::Tab => parts.push("tab".to_string()),
-                        KeyCode::Space => parts.push("space".to_string()),
-                        KeyCode::Minus => parts.push("minus".to_string()),
-                        KeyCode::Equals => parts.push("equal".to_string()),
-                        KeyCode::Backslash => parts.push("backslash".to_string()),
-                        KeyCode::LBracket => parts.push("bracketleft".to_string()),
-                        KeyCode::RBracket => parts.push("bracketright".to_string()),
-                        KeyCode::Semicolon => parts.push("semicolon".to_string()),
-                        KeyCode::Apostrophe => parts.push("apostrophe".to_string()),
-                        KeyCode::Comma => parts.push("comma".to_string()),
-                        KeyCode::Period => parts.push("dot".to_string()),
-                        KeyCode::Slash => parts.push("slash".to_string()),
-                        
-                        _ => return Some(Message::ClearError),
-                    };
-
-
-                    if parts.is_empty() {
-                        Some(Message::ClearError)
-                    } else {
-                        Some(Message::KeyRecorded(parts.join(" + ")))
-                    }
-                } else {
-                    None
-                }
-            })
-        } else {
-            Subscription::none()
-        }
-    }
-
-
-    fn view(&self) -> Element<Self::Message> {
-        view(&self.state, &self.error)
-    }
 }
 
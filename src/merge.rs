@@ -0,0 +1,132 @@
+use crate::data_model::{AppMode, GuiHotkey};
+use std::collections::BTreeSet;
+
+// Result of a three-way merge between the GUI's modes ("mine") and an
+// external edit of the swhkdrc ("theirs"), both descended from `base`.
+#[derive(Debug, Clone, Default)]
+pub struct MergeResult {
+    pub modes: Vec<AppMode>,
+    // One line per hotkey or mode both sides changed differently. The GUI
+    // side is kept; a conflicting external binding is added as inactive.
+    pub conflicts: Vec<String>,
+}
+
+// The swhkdrc only carries active bindings, so inactive rows on the GUI side
+// are kept as they are and never compared.
+fn active_hotkeys(mode: Option<&AppMode>) -> Vec<&GuiHotkey> {
+    mode.map(|m| m.hotkeys.iter().filter(|hk| hk.action.active).collect())
+        .unwrap_or_default()
+}
+
+fn find_mode<'a>(modes: &'a [AppMode], name: &str) -> Option<&'a AppMode> {
+    modes.iter().find(|m| m.name.trim() == name.trim())
+}
+
+fn find_hotkey<'a>(hotkeys: &[&'a GuiHotkey], signature: &(BTreeSet<String>, String)) -> Option<&'a GuiHotkey> {
+    hotkeys.iter().copied().find(|hk| hk.signature() == *signature)
+}
+
+pub fn three_way(base: &[AppMode], mine: &[AppMode], theirs: &[AppMode]) -> MergeResult {
+    let mut result = MergeResult::default();
+
+    // Modes only the base knows about were deleted on both sides, so only
+    // names from either side are visited.
+    let mut names: Vec<&str> = mine.iter().map(|m| m.name.as_str()).collect();
+    for mode in theirs {
+        if !names.iter().any(|n| n.trim() == mode.name.trim()) {
+            names.push(&mode.name);
+        }
+    }
+
+    for name in names {
+        let b = find_mode(base, name);
+        let m = find_mode(mine, name);
+        let t = find_mode(theirs, name);
+        let (b_active, m_active, t_active) = (active_hotkeys(b), active_hotkeys(m), active_hotkeys(t));
+
+        match (m, t) {
+            (Some(m), None) => {
+                // Deleted externally: drop it unless the GUI changed it too.
+                if b.is_some() && m_active != b_active {
+                    result.conflicts.push(format!("mode '{}' was removed from the file but edited here; kept", name.trim()));
+                    result.modes.push(m.clone());
+                } else if b.is_none() {
+                    result.modes.push(m.clone());
+                }
+            }
+            (None, Some(t)) => {
+                if b.is_some() && t_active != b_active {
+                    result.conflicts.push(format!("mode '{}' was removed here but edited in the file; restored", name.trim()));
+                    result.modes.push(t.clone());
+                } else if b.is_none() {
+                    result.modes.push(t.clone());
+                }
+            }
+            (Some(m), Some(_)) => {
+                let hotkeys = merge_hotkeys(name, m, &b_active, &t_active, &mut result.conflicts);
+                result.modes.push(AppMode { id: m.id, name: m.name.clone(), hotkeys });
+            }
+            (None, None) => {}
+        }
+    }
+
+    result
+}
+
+fn merge_hotkeys(
+    mode_name: &str,
+    mine: &AppMode,
+    base: &[&GuiHotkey],
+    theirs: &[&GuiHotkey],
+    conflicts: &mut Vec<String>,
+) -> Vec<GuiHotkey> {
+    let mut merged = Vec::new();
+
+    for hk in &mine.hotkeys {
+        if !hk.action.active {
+            merged.push(hk.clone());
+            continue;
+        }
+        let signature = hk.signature();
+        let b = find_hotkey(base, &signature);
+        let t = find_hotkey(theirs, &signature);
+        match (b, t) {
+            // Unchanged here, so the external side wins (including deletion).
+            (Some(b), _) if b == hk => merged.extend(t.cloned()),
+            // Unchanged externally, or both sides agree.
+            (_, Some(t)) if t == hk || b == Some(t) => merged.push(hk.clone()),
+            (Some(_), None) => {
+                conflicts.push(format!("{}: {} was removed from the file but edited here; kept", mode_name.trim(), hk.combo_string()));
+                merged.push(hk.clone());
+            }
+            (None, None) => merged.push(hk.clone()),
+            (_, Some(t)) => {
+                conflicts.push(format!("{}: {} changed on both sides; the file's version was added as inactive", mode_name.trim(), hk.combo_string()));
+                merged.push(hk.clone());
+                let mut external = t.clone();
+                external.action.active = false;
+                merged.push(external);
+            }
+        }
+    }
+
+    let mine_active: Vec<&GuiHotkey> = mine.hotkeys.iter().filter(|hk| hk.action.active).collect();
+    for t in theirs {
+        let signature = t.signature();
+        if find_hotkey(&mine_active, &signature).is_some() {
+            continue;
+        }
+        match find_hotkey(base, &signature) {
+            // Added externally.
+            None => merged.push((*t).clone()),
+            // Deleted here and untouched externally: stays deleted.
+            Some(b) if b == *t => {}
+            Some(_) => {
+                conflicts.push(format!("{}: {} was removed here but edited in the file; restored", mode_name.trim(), t.combo_string()));
+                merged.push((*t).clone());
+            }
+        }
+    }
+
+    merged
+}
@@ -0,0 +1,119 @@
+use crate::runner::{self, RunStatus};
+use crate::settings::AppSettings;
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sweet::{ParserInput, SwhkdParser};
+
+// Optional external checker, run as `<checker> <candidate file>`; it must exit
+// non-zero when the config is invalid. swhkd itself has no check-only mode
+// (it grabs the keyboard as soon as it parses a config), so this has to be a
+// separate program, e.g. a script with the user's own rules. Without one,
+// only the Sweet parser check below runs. The variable takes precedence over
+// the `check_command` setting.
+pub const CHECK_COMMAND_ENV: &str = "SWHKD_GUI_CHECK_COMMAND";
+// The checker is killed after this long and the config counts as rejected.
+pub const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub fn configured_checker(settings: &AppSettings) -> Option<PathBuf> {
+    std::env::var_os(CHECK_COMMAND_ENV)
+        .filter(|cmd| !cmd.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| settings.check_command.clone())
+}
+
+// A new directory only the current user can enter. Creating it fails if
+// anything, including a symlink, already has the name, so the candidate
+// can't be redirected or read by other users.
+fn private_dir() -> Result<PathBuf, String> {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.subsec_nanos()).unwrap_or_default();
+    let mut last_error = None;
+    for _ in 0..16 {
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!("swhkd-gui-preflight-{}-{nanos:x}-{n}", std::process::id()));
+        match fs::DirBuilder::new().mode(0o700).create(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => last_error = Some(e),
+            Err(e) => return Err(format!("Could not create {}: {e}", dir.display())),
+        }
+    }
+    Err(format!("Could not create a private temporary directory: {}", last_error.map(|e| e.to_string()).unwrap_or_default()))
+}
+
+// The candidate is checked from a temporary directory, so relative
+// `include`/`import` paths are made absolute against the directory the
+// config will be saved in.
+fn with_absolute_includes(config_text: &str, config_dir: &Path) -> String {
+    config_text
+        .split_inclusive('\n')
+        .map(|line| {
+            let trimmed = line.trim_start();
+            let Some((keyword, rest)) = trimmed.split_once(' ').filter(|(k, _)| *k == "include" || *k == "import") else {
+                return line.to_string();
+            };
+            let path = rest.trim();
+            if path.is_empty() || path.starts_with('~') || path.starts_with('$') || Path::new(path).is_absolute() {
+                return line.to_string();
+            }
+            let indent = &line[..line.len() - trimmed.len()];
+            let newline = if line.ends_with('\n') { "\n" } else { "" };
+            format!("{indent}{keyword} {}{newline}", config_dir.join(path).display())
+        })
+        .collect()
+}
+
+// Writes `config_text` to a temporary file and validates it the way swhkd
+// would, so a broken config never replaces the live one. `config_path` is
+// where it is about to be saved.
+pub fn check_config_text(config_text: &str, config_path: &Path, checker: Option<&Path>) -> Result<(), String> {
+    let config_text = with_absolute_includes(config_text, config_path.parent().unwrap_or(Path::new("")));
+    let dir = private_dir()?;
+    let candidate = dir.join("swhkdrc");
+    let written = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&candidate)
+        .and_then(|mut file| file.write_all(config_text.as_bytes()))
+        .map_err(|e| format!("Could not write {}: {e}", candidate.display()));
+    let result = written.and_then(|_| check_config_file(&candidate, checker));
+    let _ = fs::remove_dir_all(&dir);
+    result
+}
+
+pub fn check_config_file(path: &Path, checker: Option<&Path>) -> Result<(), String> {
+    if let Err(e) = SwhkdParser::from(ParserInput::Path(path)) {
+        return Err(format!("Config rejected by the swhkd parser, nothing was saved:\n{e}"));
+    }
+
+    let Some(checker) = checker else {
+        return Ok(());
+    };
+    let mut command = Command::new(checker);
+    command.arg(path);
+    let (stdout, mut diagnostics, status) = runner::run_program(command, CHECK_TIMEOUT, &AtomicBool::new(false));
+    match status {
+        RunStatus::Exited(Some(0)) => Ok(()),
+        RunStatus::Failed(e) => Err(format!("Could not run {}: {e}", checker.display())),
+        RunStatus::TimedOut => Err(format!(
+            "{} did not finish within {} seconds and was stopped, nothing was saved",
+            checker.display(),
+            CHECK_TIMEOUT.as_secs()
+        )),
+        status => {
+            if diagnostics.trim().is_empty() {
+                diagnostics = stdout;
+            }
+            Err(format!(
+                "Config rejected by {} ({status}), nothing was saved:\n{}",
+                checker.display(),
+                diagnostics.trim()
+            ))
+        }
+    }
+}
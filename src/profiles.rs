@@ -0,0 +1,50 @@
+use crate::data_model::AppState;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Profiles are complete GUI states saved as `<name>.json` in a `profiles`
+// directory next to the state file.
+pub fn profiles_dir(state_path: &Path) -> PathBuf {
+    state_path.parent().unwrap_or_else(|| Path::new(".")).join("profiles")
+}
+
+// Profile names become file names, so path separators and leading dots are
+// refused.
+pub fn profile_path(dir: &Path, name: &str) -> Result<PathBuf, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("The profile needs a name".to_string());
+    }
+    if name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(format!("Invalid profile name '{name}'"));
+    }
+    Ok(dir.join(format!("{name}.json")))
+}
+
+pub fn list(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| path.file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+        .collect();
+    names.sort_by_key(|name| name.to_lowercase());
+    names
+}
+
+pub fn save(dir: &Path, name: &str, state: &AppState) -> Result<(), String> {
+    state.save_to_json_file(&profile_path(dir, name)?.display().to_string())
+}
+
+// Unlike the state file, a missing profile is an error rather than an empty
+// state.
+pub fn load(dir: &Path, name: &str) -> Result<AppState, String> {
+    let path = profile_path(dir, name)?;
+    if !path.is_file() {
+        return Err(format!("No profile named '{}' in {}", name.trim(), dir.display()));
+    }
+    AppState::load_from_json_file(&path.display().to_string())
+}
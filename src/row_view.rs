@@ -0,0 +1,132 @@
+use crate::data_model::{AppState, GuiHotkey};
+use std::collections::{BTreeSet, HashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortColumn {
+    Combo,
+    Command,
+    Active,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RowFilter {
+    #[default]
+    All,
+    Inactive,
+    Conflicting,
+    Invalid,
+}
+
+impl RowFilter {
+    pub const ALL: [RowFilter; 4] = [RowFilter::All, RowFilter::Inactive, RowFilter::Conflicting, RowFilter::Invalid];
+}
+
+impl std::fmt::Display for RowFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            RowFilter::All => "All rows",
+            RowFilter::Inactive => "Inactive",
+            RowFilter::Conflicting => "Conflicting",
+            RowFilter::Invalid => "Invalid",
+        })
+    }
+}
+
+// Search, filters and sorting of the hotkey table. They only change what the
+// table shows; the modes keep their written order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RowView {
+    pub search: String,
+    pub tag_filter: Option<String>,
+    // Column and ascending flag.
+    pub sort: Option<(SortColumn, bool)>,
+    pub filter: RowFilter,
+}
+
+impl RowView {
+    fn shows(&self, hk: &GuiHotkey, conflicts: &HashSet<(BTreeSet<String>, String)>) -> bool {
+        if !hk.matches(&self.search) {
+            return false;
+        }
+        if let Some(tag) = &self.tag_filter {
+            if !hk.action.tags.contains(tag) {
+                return false;
+            }
+        }
+        match self.filter {
+            RowFilter::All => true,
+            RowFilter::Inactive => !hk.action.active,
+            RowFilter::Conflicting => hk.action.active && conflicts.contains(&hk.signature()),
+            RowFilter::Invalid => hk.validation_error().is_some(),
+        }
+    }
+
+    // Rows of the selected mode in the order the table shows them.
+    pub fn visible_rows(&self, state: &AppState) -> Vec<usize> {
+        let Some(mode) = state.modes.get(state.selected_mode) else {
+            return Vec::new();
+        };
+        let conflicts = state.conflicting_signatures(state.selected_mode);
+        let mut visible: Vec<usize> = mode
+            .hotkeys
+            .iter()
+            .enumerate()
+            .filter(|(_, hk)| self.shows(hk, &conflicts))
+            .map(|(i, _)| i)
+            .collect();
+        if let Some((column, ascending)) = self.sort {
+            visible.sort_by(|&a, &b| {
+                let (a, b) = (&mode.hotkeys[a], &mode.hotkeys[b]);
+                let ordering = match column {
+                    SortColumn::Combo => a.combo_string().cmp(&b.combo_string()),
+                    SortColumn::Command => a.action.command.to_lowercase().cmp(&b.action.command.to_lowercase()),
+                    SortColumn::Active => a.action.active.cmp(&b.action.active),
+                };
+                if ascending { ordering } else { ordering.reverse() }
+            });
+        }
+        visible
+    }
+
+    // The rows `visible[pos]` is moved to when moved up or down. The row
+    // takes its visible neighbour's place and the rows in between shift by
+    // one; a sorted table doesn't show the written order, so there is
+    // nothing to move.
+    pub fn reorder_targets(&self, visible: &[usize], pos: usize) -> (Option<usize>, Option<usize>) {
+        if self.sort.is_some() {
+            return (None, None);
+        }
+        (pos.checked_sub(1).and_then(|p| visible.get(p).copied()), visible.get(pos + 1).copied())
+    }
+
+    // Drops selected rows the table no longer shows, and a shift-click
+    // anchor among them, so bulk actions only touch rows on screen.
+    pub fn retain_visible(&self, state: &AppState, selected: &mut BTreeSet<usize>, anchor: &mut Option<usize>) {
+        let visible = self.visible_rows(state);
+        selected.retain(|row| visible.contains(row));
+        *anchor = anchor.filter(|row| visible.contains(row));
+    }
+
+    // Ascending, then descending, then unsorted.
+    pub fn toggle_sort(&mut self, column: SortColumn) {
+        self.sort = match self.sort {
+            Some((current, true)) if current == column => Some((column, false)),
+            Some((current, false)) if current == column => None,
+            _ => Some((column, true)),
+        };
+    }
+
+    // Hotkeys in the other modes that match the search, with their mode.
+    pub fn other_mode_matches<'a>(&self, state: &'a AppState) -> Vec<(usize, &'a GuiHotkey)> {
+        if self.search.trim().is_empty() {
+            return Vec::new();
+        }
+        state
+            .modes
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| *idx != state.selected_mode)
+            .flat_map(|(idx, mode)| mode.hotkeys.iter().filter(|hk| hk.matches(&self.search)).map(move |hk| (idx, hk)))
+            .collect()
+    }
+}
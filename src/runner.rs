@@ -0,0 +1,132 @@
+use std::io::Read;
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// How long a test run may take before it is killed.
+pub const RUN_TIMEOUT: Duration = Duration::from_secs(10);
+// Output beyond this many bytes per stream is dropped.
+const OUTPUT_LIMIT: usize = 64 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunStatus {
+    Exited(Option<i32>),
+    TimedOut,
+    Killed,
+    // The shell could not be started at all.
+    Failed(String),
+}
+
+impl std::fmt::Display for RunStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunStatus::Exited(Some(code)) => write!(f, "exit code {code}"),
+            RunStatus::Exited(None) => f.write_str("terminated by a signal"),
+            RunStatus::TimedOut => write!(f, "killed after {} seconds", RUN_TIMEOUT.as_secs()),
+            RunStatus::Killed => f.write_str("killed"),
+            RunStatus::Failed(e) => write!(f, "could not start: {e}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunOutput {
+    pub command: String,
+    pub stdout: String,
+    pub stderr: String,
+    pub status: RunStatus,
+}
+
+// Commands run through the user's login shell, like typing them in a
+// terminal; swhkd itself uses `sh -c`.
+pub fn user_shell() -> String {
+    std::env::var("SHELL").ok().filter(|s| !s.is_empty()).unwrap_or_else(|| "/bin/sh".to_string())
+}
+
+// Runs `command` and waits for it, killing its whole process group once
+// `timeout` has passed or `cancel` is set. Blocks the calling thread.
+pub fn run(command: &str, timeout: Duration, cancel: &AtomicBool) -> RunOutput {
+    let mut shell = Command::new(user_shell());
+    shell.arg("-c").arg(command);
+    let (stdout, stderr, status) = run_program(shell, timeout, cancel);
+    RunOutput { command: command.to_string(), stdout, stderr, status }
+}
+
+// Like `run`, for a program that is started directly. Returns its stdout,
+// stderr and how it ended.
+pub fn run_program(mut program: Command, timeout: Duration, cancel: &AtomicBool) -> (String, String, RunStatus) {
+    let mut child = match program
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => return (String::new(), String::new(), RunStatus::Failed(e.to_string())),
+    };
+
+    let stdout = child.stdout.take().map(read_limited);
+    let stderr = child.stderr.take().map(read_limited);
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break RunStatus::Exited(status.code()),
+            Ok(None) => {}
+            Err(e) => break RunStatus::Failed(e.to_string()),
+        }
+        let stop = if cancel.load(Ordering::Relaxed) {
+            Some(RunStatus::Killed)
+        } else if started.elapsed() >= timeout {
+            Some(RunStatus::TimedOut)
+        } else {
+            None
+        };
+        if let Some(status) = stop {
+            // Anything the program started shares its process group.
+            let _ = Command::new("kill").args(["-KILL", "--", &format!("-{}", child.id())]).status();
+            let _ = child.kill();
+            let _ = child.wait();
+            break status;
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+
+    // Something the command started in the background may keep the pipes
+    // open; don't wait for it for long.
+    let readers = [&stdout, &stderr];
+    let grace = Instant::now();
+    while readers.iter().any(|r| r.as_ref().is_some_and(|r| !r.1.is_finished())) && grace.elapsed() < Duration::from_millis(200) {
+        thread::sleep(Duration::from_millis(10));
+    }
+    let collect = |reader: Option<Captured>| {
+        reader
+            .map(|(bytes, _)| String::from_utf8_lossy(&bytes.lock().unwrap_or_else(|e| e.into_inner())).into_owned())
+            .unwrap_or_default()
+    };
+    (collect(stdout), collect(stderr), status)
+}
+
+type Captured = (Arc<Mutex<Vec<u8>>>, thread::JoinHandle<()>);
+
+// Reads the whole stream so the command never blocks on a full pipe, but
+// keeps only the first `OUTPUT_LIMIT` bytes.
+fn read_limited(mut stream: impl Read + Send + 'static) -> Captured {
+    let bytes = Arc::new(Mutex::new(Vec::new()));
+    let kept = Arc::clone(&bytes);
+    let reader = thread::spawn(move || {
+        let mut chunk = [0u8; 4096];
+        while let Ok(n) = stream.read(&mut chunk) {
+            if n == 0 {
+                break;
+            }
+            let mut kept = kept.lock().unwrap_or_else(|e| e.into_inner());
+            let room = OUTPUT_LIMIT.saturating_sub(kept.len());
+            kept.extend_from_slice(&chunk[..n.min(room)]);
+        }
+    });
+    (bytes, reader)
+}
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+const APP_DIR: &str = "swhkd-gui";
+const SETTINGS_FILE: &str = "settings.json";
+// Where the GUI state lived before it was configurable, relative to the
+// working directory.
+pub const LEGACY_STATE_PATH: &str = "swhkd_gui_saved.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReloadMethod {
+    // Pick systemd, a plain signal or pkexec from how swhkd is running.
+    #[default]
+    Auto,
+    Systemd,
+    Signal,
+    Pkexec,
+    // Only write the file; swhkd is reloaded by hand.
+    Manual,
+}
+
+impl ReloadMethod {
+    pub const ALL: [ReloadMethod; 5] = [
+        ReloadMethod::Auto,
+        ReloadMethod::Systemd,
+        ReloadMethod::Signal,
+        ReloadMethod::Pkexec,
+        ReloadMethod::Manual,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.name() == name.to_lowercase())
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReloadMethod::Auto => "auto",
+            ReloadMethod::Systemd => "systemd",
+            ReloadMethod::Signal => "signal",
+            ReloadMethod::Pkexec => "pkexec",
+            ReloadMethod::Manual => "manual",
+        }
+    }
+}
+
+impl std::fmt::Display for ReloadMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ReloadMethod::Auto => "Automatic",
+            ReloadMethod::Systemd => "systemd user unit",
+            ReloadMethod::Signal => "SIGHUP",
+            ReloadMethod::Pkexec => "SIGHUP via pkexec",
+            ReloadMethod::Manual => "Don't reload",
+        })
+    }
+}
+
+// Persisted in `$XDG_CONFIG_HOME/swhkd-gui/settings.json`. Missing fields
+// fall back to their defaults so older files keep loading.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    // `None` looks for an existing swhkdrc, see `discovery::discover`.
+    pub config_path: Option<PathBuf>,
+    pub state_path: PathBuf,
+    pub dialog_dir: PathBuf,
+    pub reload_method: ReloadMethod,
+    // Earlier versions of the config kept next to it on every save; 0
+    // disables backups.
+    pub backup_count: usize,
+    // Extra program that checks the config before it is saved, see
+    // `preflight::CHECK_COMMAND_ENV`.
+    pub check_command: Option<PathBuf>,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        let state_dir = dirs::state_dir()
+            .or_else(dirs::data_local_dir)
+            .unwrap_or_else(|| home.join(".local/state"));
+        Self {
+            config_path: None,
+            state_path: state_dir.join(APP_DIR).join("state.json"),
+            dialog_dir: home,
+            reload_method: ReloadMethod::Auto,
+            backup_count: 5,
+            check_command: None,
+        }
+    }
+}
+
+pub fn settings_dir() -> PathBuf {
+    dirs::config_dir().unwrap_or_else(|| PathBuf::from(".")).join(APP_DIR)
+}
+
+pub fn settings_file() -> PathBuf {
+    settings_dir().join(SETTINGS_FILE)
+}
+
+impl AppSettings {
+    // A missing file gives the defaults; an unreadable one is an error so it
+    // isn't silently replaced on the next save.
+    pub fn load() -> Result<Self, String> {
+        let path = settings_file();
+        match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).map_err(|e| format!("Invalid settings in {}: {e}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("Could not read {}: {e}", path.display())),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = settings_file();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Could not create {}: {e}", parent.display()))?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(&path, json).map_err(|e| format!("Could not write {}: {e}", path.display()))
+    }
+}
+
+// Values given on the command line for a single run; never saved.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SettingsOverrides {
+    pub config_path: Option<PathBuf>,
+    pub state_path: Option<PathBuf>,
+    pub dialog_dir: Option<PathBuf>,
+    pub reload_method: Option<ReloadMethod>,
+    pub backup_count: Option<usize>,
+}
+
+impl SettingsOverrides {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn apply(&self, settings: &AppSettings) -> AppSettings {
+        AppSettings {
+            config_path: self.config_path.clone().or_else(|| settings.config_path.clone()),
+            state_path: self.state_path.clone().unwrap_or_else(|| settings.state_path.clone()),
+            dialog_dir: self.dialog_dir.clone().unwrap_or_else(|| settings.dialog_dir.clone()),
+            reload_method: self.reload_method.unwrap_or(settings.reload_method),
+            backup_count: self.backup_count.unwrap_or(settings.backup_count),
+            check_command: settings.check_command.clone(),
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use crate::settings;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const SNIPPETS_FILE: &str = "snippets.json";
+
+// A reusable command. `{{name}}` and `{{name:default}}` mark placeholders
+// that are filled in when the snippet is inserted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snippet {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placeholder {
+    pub name: String,
+    pub default: Option<String>,
+}
+
+// Start and end byte offsets of every `{{...}}` in `command`, with its
+// parsed content.
+fn placeholder_spans(command: &str) -> Vec<(usize, usize, Placeholder)> {
+    let mut spans = Vec::new();
+    let mut from = 0;
+    while let Some(start) = command[from..].find("{{").map(|i| from + i) {
+        let Some(end) = command[start + 2..].find("}}").map(|i| start + 2 + i + 2) else {
+            break;
+        };
+        let inner = &command[start + 2..end - 2];
+        let (name, default) = match inner.split_once(':') {
+            Some((name, default)) => (name, Some(default.to_string())),
+            None => (inner, None),
+        };
+        spans.push((start, end, Placeholder { name: name.trim().to_string(), default }));
+        from = end;
+    }
+    spans
+}
+
+impl Snippet {
+    fn new(name: &str, command: &str, description: &str) -> Self {
+        Self { name: name.to_string(), command: command.to_string(), description: description.to_string() }
+    }
+
+    // Each placeholder once, in the order they first appear.
+    pub fn placeholders(&self) -> Vec<Placeholder> {
+        let mut placeholders: Vec<Placeholder> = Vec::new();
+        for (_, _, placeholder) in placeholder_spans(&self.command) {
+            if !placeholders.iter().any(|p| p.name == placeholder.name) {
+                placeholders.push(placeholder);
+            }
+        }
+        placeholders
+    }
+
+    // The command with every placeholder replaced by its value, or its
+    // default when no value was given.
+    pub fn fill(&self, values: &HashMap<String, String>) -> String {
+        let mut command = String::new();
+        let mut from = 0;
+        for (start, end, placeholder) in placeholder_spans(&self.command) {
+            command.push_str(&self.command[from..start]);
+            let value = values.get(&placeholder.name).filter(|v| !v.is_empty());
+            command.push_str(value.or(placeholder.default.as_ref()).map(String::as_str).unwrap_or_default());
+            from = end;
+        }
+        command.push_str(&self.command[from..]);
+        command
+    }
+
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        query.is_empty()
+            || self.name.to_lowercase().contains(&query)
+            || self.command.to_lowercase().contains(&query)
+            || self.description.to_lowercase().contains(&query)
+    }
+}
+
+pub fn builtin() -> Vec<Snippet> {
+    vec![
+        Snippet::new("Screenshot of an area", "grim -g \"$(slurp)\" {{file:~/Pictures/screenshot.png}}", "Select an area with slurp and save it"),
+        Snippet::new("Screenshot of an area to the clipboard", "grim -g \"$(slurp)\" - | wl-copy", "Select an area with slurp and copy it"),
+        Snippet::new("Screenshot of an output", "grim -o {{output:eDP-1}} {{file:~/Pictures/screenshot.png}}", "Save one monitor"),
+        Snippet::new("Change volume", "pactl set-sink-volume @DEFAULT_SINK@ {{step:+5%}}", "Use -5% to lower it"),
+        Snippet::new("Mute speakers", "pactl set-sink-mute @DEFAULT_SINK@ toggle", ""),
+        Snippet::new("Mute microphone", "pactl set-source-mute @DEFAULT_SOURCE@ toggle", ""),
+        Snippet::new("Media player", "playerctl {{action:play-pause}}", "Actions: play-pause, next, previous, stop"),
+        Snippet::new("Media player by name", "playerctl --player={{player:spotify}} {{action:play-pause}}", ""),
+        Snippet::new("Change brightness", "brightnessctl set {{step:5%+}}", "Use 5%- to dim"),
+        Snippet::new("Notification", "notify-send \"{{title}}\" \"{{body}}\"", ""),
+        Snippet::new("Open a URL", "xdg-open {{url:https://}}", "Opens in the default browser"),
+        Snippet::new("Switch sway workspace", "swaymsg workspace number {{number:1}}", ""),
+        Snippet::new("Lock the screen", "swaylock -f -c {{color:000000}}", ""),
+    ]
+}
+
+// User snippets live next to the settings, as a JSON list of
+// `{ "name", "command", "description" }` objects.
+pub fn user_snippets_file() -> PathBuf {
+    settings::settings_dir().join(SNIPPETS_FILE)
+}
+
+pub fn load_user(path: &Path) -> Result<Vec<Snippet>, String> {
+    match fs::read_to_string(path) {
+        Ok(json) => serde_json::from_str(&json).map_err(|e| format!("Invalid snippets in {}: {e}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Could not read {}: {e}", path.display())),
+    }
+}
+
+// User snippets first, then the built-in ones. An unreadable user file is
+// reported but doesn't hide the built-in snippets.
+pub fn library(path: &Path) -> (Vec<Snippet>, Option<String>) {
+    let (mut snippets, error) = match load_user(path) {
+        Ok(user) => (user, None),
+        Err(e) => (Vec::new(), Some(e)),
+    };
+    snippets.extend(builtin());
+    (snippets, error)
+}
@@ -0,0 +1,128 @@
+use crate::data_model::{AppState, GuiHotkey};
+
+// Starter modes. They are written as swhkdrc text so descriptions and tags
+// go through the same parser as a user's own config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModeTemplate {
+    pub name: &'static str,
+    config: &'static str,
+}
+
+pub const TEMPLATES: [ModeTemplate; 3] = [
+    ModeTemplate {
+        name: "Window management",
+        config: "\
+# description: Focus the window to the left
+# tags: windows
+super + h
+    swaymsg focus left
+
+# description: Focus the window below
+# tags: windows
+super + j
+    swaymsg focus down
+
+# description: Focus the window above
+# tags: windows
+super + k
+    swaymsg focus up
+
+# description: Focus the window to the right
+# tags: windows
+super + l
+    swaymsg focus right
+
+# description: Toggle fullscreen
+# tags: windows
+super + f
+    swaymsg fullscreen toggle
+
+# description: Toggle floating
+# tags: windows
+super + shift + space
+    swaymsg floating toggle
+
+# description: Close the focused window
+# tags: windows
+super + shift + q
+    swaymsg kill
+",
+    },
+    ModeTemplate {
+        name: "Media keys",
+        config: "\
+# description: Volume up
+# tags: media
+xf86audioraisevolume
+    wpctl set-volume -l 1.0 @DEFAULT_AUDIO_SINK@ 5%+
+
+# description: Volume down
+# tags: media
+xf86audiolowervolume
+    wpctl set-volume @DEFAULT_AUDIO_SINK@ 5%-
+
+# description: Mute
+# tags: media
+xf86audiomute
+    wpctl set-mute @DEFAULT_AUDIO_SINK@ toggle
+
+# description: Play or pause
+# tags: media
+xf86audioplay
+    playerctl play-pause
+
+# description: Next track
+# tags: media
+xf86audionext
+    playerctl next
+
+# description: Previous track
+# tags: media
+xf86audioprev
+    playerctl previous
+
+# description: Brightness up
+# tags: media
+xf86monbrightnessup
+    brightnessctl set 5%+
+
+# description: Brightness down
+# tags: media
+xf86monbrightnessdown
+    brightnessctl set 5%-
+",
+    },
+    ModeTemplate {
+        name: "Screenshot",
+        config: "\
+# description: Screenshot of all outputs
+# tags: screenshot
+print
+    grim ~/Pictures/screenshot-$(date +%Y%m%d-%H%M%S).png
+
+# description: Screenshot of a selected area
+# tags: screenshot
+shift + print
+    grim -g \"$(slurp)\" ~/Pictures/screenshot-$(date +%Y%m%d-%H%M%S).png
+
+# description: Copy a selected area to the clipboard
+# tags: screenshot
+ctrl + shift + print
+    grim -g \"$(slurp)\" - | wl-copy
+",
+    },
+];
+
+impl ModeTemplate {
+    pub fn hotkeys(&self) -> Vec<GuiHotkey> {
+        let mut parsed = AppState::default();
+        parsed.load_from_swhkd_config_str(self.config);
+        parsed.modes.into_iter().flat_map(|m| m.hotkeys).collect()
+    }
+}
+
+impl std::fmt::Display for ModeTemplate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name)
+    }
+}
@@ -0,0 +1,114 @@
+use crate::data_model::{AppMode, AppState};
+
+fn mode_index(modes: &[AppMode], id: u64) -> Option<usize> {
+    modes.iter().position(|m| m.id == id)
+}
+
+// The id of the mode holding hotkey `id`, and its row there.
+fn locate(modes: &[AppMode], id: u64) -> Option<(u64, usize)> {
+    modes
+        .iter()
+        .find_map(|m| m.hotkeys.iter().position(|hk| hk.id == id).map(|row| (m.id, row)))
+}
+
+fn revert_field<T: PartialEq + Clone>(now: &mut T, before: &T, after: &T) {
+    if before != after && now == after {
+        *now = before.clone();
+    }
+}
+
+// Undoes the edit that turned `before` into `after` while keeping everything
+// changed since. Rows it added are removed, rows it moved or deleted go back
+// after the row that preceded them, and fields it set are reset unless they
+// were edited again. Modes and hotkeys are matched by id, so the rest of the
+// state may have changed in any way.
+pub fn revert(state: &mut AppState, before: &[AppMode], after: &[AppMode]) {
+    let selected = state.modes.get(state.selected_mode).map(|m| m.id);
+
+    // Rows it added, wherever they are now.
+    for hk in after.iter().flat_map(|m| &m.hotkeys) {
+        if locate(before, hk.id).is_none() {
+            for mode in &mut state.modes {
+                mode.hotkeys.retain(|h| h.id != hk.id);
+            }
+        }
+    }
+
+    // Modes it added go again, unless hotkeys were added to them since.
+    state
+        .modes
+        .retain(|m| mode_index(before, m.id).is_some() || mode_index(after, m.id).is_none() || !m.hotkeys.is_empty());
+
+    // Modes it deleted come back empty; their rows follow below.
+    for (i, mode) in before.iter().enumerate() {
+        if mode_index(after, mode.id).is_some() || mode_index(&state.modes, mode.id).is_some() {
+            continue;
+        }
+        let at = before[..i]
+            .iter()
+            .rev()
+            .find_map(|m| mode_index(&state.modes, m.id))
+            .map_or(0, |p| p + 1);
+        let name = state.unique_mode_name(&mode.name);
+        state.modes.insert(at, AppMode { id: mode.id, name, hotkeys: Vec::new() });
+    }
+
+    // A row it moved only goes back if it is still where the edit put it; one
+    // it deleted only if it hasn't come back some other way.
+    for mode in before {
+        for (row, hk) in mode.hotkeys.iter().enumerate() {
+            let moved_to = locate(after, hk.id).map(|(m, _)| m);
+            if moved_to == Some(mode.id) {
+                continue;
+            }
+            let Some(target) = mode_index(&state.modes, mode.id) else {
+                continue;
+            };
+            let restored = match (locate(&state.modes, hk.id), moved_to) {
+                (None, None) => hk.clone(),
+                (Some((now, r)), Some(then)) if now == then => match mode_index(&state.modes, now) {
+                    Some(m) => state.modes[m].hotkeys.remove(r),
+                    None => continue,
+                },
+                _ => continue,
+            };
+            let hotkeys = &mut state.modes[target].hotkeys;
+            let at = mode.hotkeys[..row]
+                .iter()
+                .rev()
+                .find_map(|p| hotkeys.iter().position(|h| h.id == p.id))
+                .map_or(0, |p| p + 1);
+            hotkeys.insert(at, restored);
+        }
+    }
+
+    for then in after.iter().flat_map(|m| &m.hotkeys) {
+        let Some(was) = before.iter().flat_map(|m| &m.hotkeys).find(|h| h.id == then.id) else {
+            continue;
+        };
+        let Some(now) = state.modes.iter_mut().flat_map(|m| m.hotkeys.iter_mut()).find(|h| h.id == then.id) else {
+            continue;
+        };
+        revert_field(&mut now.modifiers, &was.modifiers, &then.modifiers);
+        revert_field(&mut now.key, &was.key, &then.key);
+        let (now, was, then) = (&mut now.action, &was.action, &then.action);
+        revert_field(&mut now.command, &was.command, &then.command);
+        revert_field(&mut now.active, &was.active, &then.active);
+        revert_field(&mut now.layer_id, &was.layer_id, &then.layer_id);
+        revert_field(&mut now.description, &was.description, &then.description);
+        revert_field(&mut now.env, &was.env, &then.env);
+        revert_field(&mut now.working_dir, &was.working_dir, &then.working_dir);
+        // Tags one at a time, so tags added or removed since stay that way.
+        for tag in then.tags.iter().filter(|t| !was.tags.contains(t)) {
+            now.tags.retain(|t| t != tag);
+        }
+        for (i, tag) in was.tags.iter().enumerate().filter(|(_, t)| !then.tags.contains(t)) {
+            if !now.tags.contains(tag) {
+                now.tags.insert(i.min(now.tags.len()), tag.clone());
+            }
+        }
+    }
+
+    state.selected_mode = selected.and_then(|id| mode_index(&state.modes, id)).unwrap_or(0);
+    state.ensure_mode();
+}
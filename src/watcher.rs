@@ -0,0 +1,84 @@
+use crate::interface::Message;
+use iced::futures::channel::mpsc;
+use iced::futures::{SinkExt, StreamExt};
+use iced::Subscription;
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// The config itself plus every file it pulls in with `include`/`import`,
+// resolved relative to the config's directory.
+pub fn watched_files(config: &Path) -> Vec<PathBuf> {
+    let mut files = vec![config.to_path_buf()];
+    let Ok(contents) = fs::read_to_string(config) else {
+        return files;
+    };
+    let base = config.parent().unwrap_or(Path::new("/"));
+    for line in contents.lines() {
+        let line = line.trim();
+        let Some(target) = line.strip_prefix("include ").or_else(|| line.strip_prefix("import ")) else {
+            continue;
+        };
+        let target = target.trim().trim_matches('"');
+        let target = match target.strip_prefix("~/") {
+            Some(rest) => std::env::var("HOME").map(|home| PathBuf::from(home).join(rest)).unwrap_or_default(),
+            None => base.join(target),
+        };
+        if !target.as_os_str().is_empty() && !files.contains(&target) {
+            files.push(target);
+        }
+    }
+    files
+}
+
+// Current contents of each watched file; missing files read as `None`.
+pub fn snapshot(files: &[PathBuf]) -> Vec<(PathBuf, Option<String>)> {
+    files
+        .iter()
+        .map(|path| (path.clone(), fs::read_to_string(path).ok()))
+        .collect()
+}
+
+// Emits `ConfigChangedOnDisk` whenever one of `files` is written, replaced or
+// removed. Parent directories are watched rather than the files so editors
+// that save by renaming a temporary file over the original are noticed too.
+pub fn watch(files: Vec<PathBuf>) -> Subscription<Message> {
+    struct ConfigWatcher;
+
+    iced::subscription::channel(
+        (std::any::TypeId::of::<ConfigWatcher>(), files.clone()),
+        16,
+        move |mut output| async move {
+            let (tx, mut rx) = mpsc::unbounded();
+            let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let _ = tx.unbounded_send(event);
+            });
+            let watcher = watcher.and_then(|mut watcher| {
+                let mut dirs: Vec<&Path> = files.iter().filter_map(|f| f.parent()).filter(|d| d.is_dir()).collect();
+                dirs.sort();
+                dirs.dedup();
+                for dir in dirs {
+                    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+                }
+                Ok(watcher)
+            });
+            if let Err(e) = &watcher {
+                let _ = output.send(Message::ShowError(format!("Not watching the config for changes: {e}"))).await;
+            }
+
+            loop {
+                match rx.next().await {
+                    Some(Ok(event))
+                        if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_))
+                            && event.paths.iter().any(|p| files.contains(p)) =>
+                    {
+                        let _ = output.send(Message::ConfigChangedOnDisk).await;
+                    }
+                    Some(_) => {}
+                    // The watcher failed to start and dropped its sender.
+                    None => std::future::pending::<()>().await,
+                }
+            }
+        },
+    )
+}
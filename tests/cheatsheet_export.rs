@@ -0,0 +1,89 @@
+mod data_model {
+    include!("../src/data_model.rs");
+}
+mod cheatsheet {
+    include!("../src/cheatsheet.rs");
+}
+
+use cheatsheet::CheatSheetFormat;
+use data_model::AppState;
+
+fn sample() -> AppState {
+    let mut state = AppState::default();
+    state.load_from_swhkd_config_str(concat!(
+        "# description: Run *the* [build] <fast> | log_all\n",
+        "super + b\n",
+        "    notify-send \"`date`\" | tee ``log``\n",
+        "\n",
+        "super + a\n",
+        "    `which foot`\n",
+        "\n",
+        "mode media\n",
+        "endmode\n",
+    ));
+    state.modes[0].hotkeys.push(data_model::GuiHotkey::new(
+        ["super".to_string()].into_iter().collect(),
+        "z".to_string(),
+        data_model::GuiAction { command: "hidden".to_string(), active: false, ..Default::default() },
+    ));
+    state
+}
+
+#[test]
+fn markdown_keeps_commands_and_escapes_text() {
+    let markdown = cheatsheet::render(&sample(), CheatSheetFormat::Markdown);
+    let lines: Vec<&str> = markdown.lines().collect();
+    assert_eq!(lines[0], "# SWHKD Keybindings");
+    assert_eq!(lines[2], "## Default");
+    assert_eq!(lines[4], "| Keys | Description | Command |");
+    // Sorted by key; backticks in commands survive inside a longer fence.
+    assert_eq!(lines[6], "| `super + a` |  | `` `which foot` `` |");
+    assert_eq!(
+        lines[7],
+        "| `super + b` | Run \\*the\\* \\[build\\] \\<fast\\> \\| log\\_all | ``` notify-send \"`date`\" \\| tee ``log`` ``` |"
+    );
+    assert_eq!(&lines[8..], ["", "## media", "", "_No active bindings._"]);
+    assert!(!markdown.contains("hidden"));
+}
+
+#[test]
+fn html_escapes_every_field() {
+    let html = cheatsheet::render(&sample(), CheatSheetFormat::Html);
+    assert!(html.starts_with("<!DOCTYPE html>\n"));
+    assert!(html.ends_with("</body>\n</html>\n"));
+    assert!(html.contains("<h2>Default</h2>\n<table>\n<tr><th>Keys</th><th>Description</th><th>Command</th></tr>\n"));
+    assert!(html.contains(
+        "<tr><td><kbd>super</kbd> + <kbd>b</kbd></td><td>Run *the* [build] &lt;fast&gt; | log_all</td>\
+         <td><code>notify-send &quot;`date`&quot; | tee ``log``</code></td></tr>\n"
+    ));
+    assert!(html.contains("<h2>media</h2>\n<p><em>No active bindings.</em></p>\n"));
+    assert!(html.find("<kbd>a</kbd>").unwrap() < html.find("<kbd>b</kbd>").unwrap());
+    assert!(!html.contains("hidden"));
+}
+
+#[test]
+fn svg_escapes_commands_as_xml() {
+    let mut state = sample();
+    state.modes[1].hotkeys.push(data_model::GuiHotkey::new(
+        ["ctrl".to_string()].into_iter().collect(),
+        "p".to_string(),
+        data_model::GuiAction {
+            command: "sort < \"in file\" && echo 'done' > out".to_string(),
+            description: Some("Sort & <save>".to_string()),
+            ..Default::default()
+        },
+    ));
+    let svg = cheatsheet::render(&state, CheatSheetFormat::Svg);
+    assert!(svg.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.ends_with("</svg>\n"));
+    assert!(svg.contains(">sort &lt; &quot;in file&quot; &amp;&amp; echo 'done' &gt; out</text>"));
+    assert!(svg.contains(">Sort &amp; &lt;save&gt;</text>"));
+    assert!(svg.contains(">notify-send &quot;`date`&quot; | tee ``log``</text>"));
+    assert!(svg.contains(">Run *the* [build] &lt;fast&gt; | log_all</text>"));
+    // Nothing unescaped is left between the tags.
+    for text in svg.split('>').filter_map(|part| part.split('<').next()) {
+        assert!(!text.contains('"'), "{text}");
+        assert!(text.split('&').skip(1).all(|rest| ["amp;", "lt;", "gt;", "quot;"].iter().any(|e| rest.starts_with(e))), "{text}");
+    }
+    assert!(!svg.contains("hidden"));
+}
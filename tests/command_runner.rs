@@ -0,0 +1,37 @@
+mod runner {
+    include!("../src/runner.rs");
+}
+
+use runner::RunStatus;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[test]
+fn output_and_exit_code_are_captured() {
+    let cancel = AtomicBool::new(false);
+    let output = runner::run("echo out; echo err >&2; exit 3", Duration::from_secs(5), &cancel);
+    assert_eq!(output.stdout, "out\n");
+    assert_eq!(output.stderr, "err\n");
+    assert_eq!(output.status, RunStatus::Exited(Some(3)));
+}
+
+#[test]
+fn hanging_commands_are_killed() {
+    let started = Instant::now();
+    let cancel = AtomicBool::new(false);
+    let output = runner::run("echo started; sleep 30", Duration::from_millis(300), &cancel);
+    assert_eq!(output.status, RunStatus::TimedOut);
+    assert_eq!(output.stdout, "started\n");
+    assert!(started.elapsed() < Duration::from_secs(5));
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&cancel);
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(200));
+        flag.store(true, Ordering::Relaxed);
+    });
+    let output = runner::run("sleep 30", Duration::from_secs(20), &cancel);
+    assert_eq!(output.status, RunStatus::Killed);
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
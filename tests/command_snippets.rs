@@ -0,0 +1,58 @@
+mod settings {
+    include!("../src/settings.rs");
+}
+mod snippets {
+    include!("../src/snippets.rs");
+}
+
+use snippets::{Placeholder, Snippet};
+use std::collections::HashMap;
+
+fn snippet(command: &str) -> Snippet {
+    Snippet { name: "test".to_string(), command: command.to_string(), description: String::new() }
+}
+
+#[test]
+fn placeholders_are_filled_with_values_or_defaults() {
+    let snippet = snippet("playerctl --player={{player:spotify}} {{ action }} && echo {{action}}");
+    assert_eq!(
+        snippet.placeholders(),
+        vec![
+            Placeholder { name: "player".to_string(), default: Some("spotify".to_string()) },
+            Placeholder { name: "action".to_string(), default: None },
+        ]
+    );
+
+    let mut values = HashMap::new();
+    values.insert("action".to_string(), "next".to_string());
+    assert_eq!(snippet.fill(&values), "playerctl --player=spotify next && echo next");
+    values.insert("player".to_string(), "mpv".to_string());
+    assert_eq!(snippet.fill(&values), "playerctl --player=mpv next && echo next");
+    assert_eq!(self::snippet("echo {{unclosed").fill(&values), "echo {{unclosed");
+}
+
+#[test]
+fn user_snippets_come_before_the_built_in_ones() {
+    let dir = std::env::temp_dir().join(format!("swhkd-gui-snippets-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("snippets.json");
+
+    let (library, error) = snippets::library(&file);
+    assert!(error.is_none());
+    assert_eq!(library, snippets::builtin());
+
+    std::fs::write(&file, r#"[{ "name": "Terminal here", "command": "foot -D {{dir:~}}" }]"#).unwrap();
+    let (library, _) = snippets::library(&file);
+    assert_eq!(library[0].name, "Terminal here");
+    assert_eq!(library.len(), snippets::builtin().len() + 1);
+    let found: Vec<&str> = library.iter().filter(|s| s.matches("GRIM")).map(|s| s.name.as_str()).collect();
+    assert_eq!(found.len(), 3);
+    assert!(library.iter().filter(|s| s.matches("pactl")).count() >= 2);
+
+    std::fs::write(&file, "{ broken").unwrap();
+    let (library, error) = snippets::library(&file);
+    assert!(error.is_some());
+    assert_eq!(library, snippets::builtin());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
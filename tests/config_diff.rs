@@ -0,0 +1,42 @@
+mod diff {
+    include!("../src/diff.rs");
+}
+
+use diff::DiffLine;
+
+#[test]
+fn changed_binding_shows_as_removed_and_added() {
+    let old = "super + t\n    alacritty\n\nsuper + b\n    firefox\n";
+    let new = "super + t\n    foot\n\nsuper + b\n    firefox\n";
+    let lines = diff::diff_lines(old, new);
+    assert!(diff::has_changes(&lines));
+    assert_eq!(
+        lines,
+        vec![
+            DiffLine::Same("super + t".into()),
+            DiffLine::Removed("    alacritty".into()),
+            DiffLine::Added("    foot".into()),
+            DiffLine::Same(String::new()),
+            DiffLine::Same("super + b".into()),
+            DiffLine::Same("    firefox".into()),
+        ]
+    );
+
+    let side_by_side = diff::side_by_side(&lines);
+    assert_eq!(side_by_side.len(), 5);
+    assert_eq!(side_by_side[1], (Some(&lines[1]), Some(&lines[2])));
+}
+
+#[test]
+fn unified_view_skips_distant_unchanged_lines() {
+    let old: String = (0..20).map(|i| format!("line {i}\n")).collect();
+    let new = old.replace("line 10\n", "line ten\n");
+    let lines = diff::diff_lines(&old, &new);
+    let rows = diff::unified(&lines, 2);
+
+    // Gap, two lines of context, the change, two more lines, gap.
+    assert_eq!(rows.len(), 1 + 2 + 2 + 2 + 1);
+    assert!(rows.first().unwrap().is_none());
+    assert!(rows.last().unwrap().is_none());
+    assert!(!diff::has_changes(&diff::diff_lines(&old, &old)));
+}
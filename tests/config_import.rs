@@ -0,0 +1,126 @@
+mod data_model {
+    include!("../src/data_model.rs");
+}
+mod config_import {
+    include!("../src/config_import.rs");
+}
+mod undo {
+    include!("../src/undo.rs");
+}
+
+use config_import::{ConflictPolicy, ImportOutcome, ImportPlan, ImportTarget};
+use data_model::AppState;
+
+fn parse(text: &str) -> AppState {
+    let mut state = AppState::default();
+    state.load_from_swhkd_config_str(text);
+    state
+}
+
+fn current() -> AppState {
+    parse("super + t\n    alacritty\n\nsuper + b\n    firefox\n\nmode resize\n\nh\n    resize left\n\nendmode\n")
+}
+
+const IMPORTED: &str = "\
+super + t
+    foot
+
+super + b
+    firefox
+
+super + m
+    mpv
+
+mode gaming
+
+super + g
+    steam
+
+endmode
+";
+
+fn outcomes(plan: &ImportPlan, state: &AppState) -> Vec<(String, ImportOutcome)> {
+    plan.apply(state).rows.into_iter().map(|row| (row.combo, row.outcome)).collect()
+}
+
+#[test]
+fn every_mode_in_the_file_is_mapped_by_name() {
+    let state = current();
+    let plan = ImportPlan::new(parse(IMPORTED).modes, &state.modes);
+    assert_eq!(plan.targets, vec![ImportTarget::Existing(0), ImportTarget::New("gaming".to_string())]);
+
+    let result = plan.apply(&state);
+    assert_eq!(result.modes.len(), 3);
+    assert_eq!(result.modes[2].name, "gaming");
+    assert_eq!(result.modes[2].hotkeys[0].action.command, "steam");
+    assert_eq!(state.modes.len(), 2, "applying only previews");
+}
+
+#[test]
+fn conflicts_follow_the_chosen_policy() {
+    let state = current();
+    let mut plan = ImportPlan::new(parse(IMPORTED).modes, &state.modes);
+    plan.targets[1] = ImportTarget::Ignore;
+
+    let expected = |conflict| {
+        vec![
+            ("super + t".to_string(), conflict),
+            ("super + b".to_string(), ImportOutcome::AlreadyPresent),
+            ("super + m".to_string(), ImportOutcome::Added),
+        ]
+    };
+    assert_eq!(outcomes(&plan, &state), expected(ImportOutcome::Skipped));
+    let commands = |plan: &ImportPlan| -> Vec<(String, bool)> {
+        plan.apply(&state).modes[0]
+            .hotkeys
+            .iter()
+            .map(|hk| (hk.action.command.clone(), hk.action.active))
+            .collect()
+    };
+    assert_eq!(commands(&plan), vec![("alacritty".into(), true), ("firefox".into(), true), ("mpv".into(), true)]);
+
+    plan.policy = ConflictPolicy::Overwrite;
+    assert_eq!(outcomes(&plan, &state), expected(ImportOutcome::Overwrote));
+    assert_eq!(commands(&plan), vec![("foot".into(), true), ("firefox".into(), true), ("mpv".into(), true)]);
+
+    plan.policy = ConflictPolicy::KeepBoth;
+    assert_eq!(outcomes(&plan, &state), expected(ImportOutcome::AddedInactive));
+    assert_eq!(
+        commands(&plan),
+        vec![("alacritty".into(), true), ("firefox".into(), true), ("foot".into(), false), ("mpv".into(), true)]
+    );
+    assert!(AppState { modes: plan.apply(&state).modes, ..current() }.check_duplicate_bindings().is_ok());
+}
+
+#[test]
+fn new_modes_get_unique_names_and_can_be_shared() {
+    let state = current();
+    let mut plan = ImportPlan::new(parse(IMPORTED).modes, &state.modes);
+    plan.targets = vec![ImportTarget::New("resize".to_string()), ImportTarget::New("resize".to_string())];
+
+    let result = plan.apply(&state);
+    assert_eq!(result.modes.len(), 3);
+    assert_eq!(result.modes[2].name, "resize_2");
+    assert_eq!(result.modes[2].hotkeys.len(), 4);
+    assert!(result.rows.iter().all(|row| row.mode == "resize_2"));
+}
+
+#[test]
+fn undoing_an_import_keeps_edits_made_after_it() {
+    let mut state = current();
+    let original = state.modes.clone();
+    let mut plan = ImportPlan::new(parse(IMPORTED).modes, &state.modes);
+    plan.policy = ConflictPolicy::Overwrite;
+    state.modes = plan.apply(&state).modes;
+    let after = state.modes.clone();
+    assert_eq!(state.modes.len(), 3);
+
+    state.modes[0].hotkeys[1].action.command = "chromium".to_string();
+    state.modes[1].hotkeys[0].action.description = Some("Resize".to_string());
+    undo::revert(&mut state, &original, &after);
+
+    let commands: Vec<&str> = state.modes[0].hotkeys.iter().map(|hk| hk.action.command.as_str()).collect();
+    assert_eq!(commands, vec!["alacritty", "chromium"]);
+    assert_eq!(state.modes.len(), 2);
+    assert_eq!(state.modes[1].hotkeys[0].action.description.as_deref(), Some("Resize"));
+}
@@ -0,0 +1,70 @@
+mod data_model {
+    include!("../src/data_model.rs");
+}
+mod merge {
+    include!("../src/merge.rs");
+}
+
+use data_model::AppState;
+
+fn modes(config: &str) -> Vec<data_model::AppMode> {
+    let mut state = AppState::default();
+    state.load_from_swhkd_config_str(config);
+    state.modes
+}
+
+fn commands(mode: &data_model::AppMode) -> Vec<(String, bool)> {
+    mode.hotkeys
+        .iter()
+        .map(|hk| (hk.action.command.clone(), hk.action.active))
+        .collect()
+}
+
+#[test]
+fn non_overlapping_edits_are_combined() {
+    let base = modes("super + t\n    alacritty\n\nsuper + b\n    firefox\n");
+    // GUI changed the terminal, the file gained a new binding.
+    let mine = modes("super + t\n    foot\n\nsuper + b\n    firefox\n");
+    let theirs = modes("super + t\n    alacritty\n\nsuper + b\n    firefox\n\nsuper + e\n    thunar\n");
+
+    let merged = merge::three_way(&base, &mine, &theirs);
+    assert!(merged.conflicts.is_empty(), "{:?}", merged.conflicts);
+    assert_eq!(
+        commands(&merged.modes[0]),
+        vec![("foot".into(), true), ("firefox".into(), true), ("thunar".into(), true)]
+    );
+}
+
+#[test]
+fn deletions_on_either_side_are_kept() {
+    let base = modes("super + t\n    alacritty\n\nsuper + b\n    firefox\n");
+    let mine = modes("super + t\n    alacritty\n");
+    let theirs = modes("super + b\n    firefox\n");
+
+    let merged = merge::three_way(&base, &mine, &theirs);
+    assert!(merged.conflicts.is_empty(), "{:?}", merged.conflicts);
+    assert!(merged.modes[0].hotkeys.is_empty());
+}
+
+#[test]
+fn conflicting_edits_keep_mine_and_add_theirs_inactive() {
+    let base = modes("super + t\n    alacritty\n");
+    let mine = modes("super + t\n    foot\n");
+    let theirs = modes("super + t\n    kitty\n");
+
+    let merged = merge::three_way(&base, &mine, &theirs);
+    assert_eq!(merged.conflicts.len(), 1);
+    assert_eq!(commands(&merged.modes[0]), vec![("foot".into(), true), ("kitty".into(), false)]);
+}
+
+#[test]
+fn modes_added_in_the_file_are_merged_in() {
+    let base = modes("super + t\n    alacritty\n");
+    let mine = base.clone();
+    let theirs = modes("super + t\n    alacritty\n\nmode resize\nh\n    bspc node -z left -20 0\nendmode\n");
+
+    let merged = merge::three_way(&base, &mine, &theirs);
+    assert!(merged.conflicts.is_empty(), "{:?}", merged.conflicts);
+    assert_eq!(merged.modes.len(), 2);
+    assert_eq!(merged.modes[1].name, "resize");
+}
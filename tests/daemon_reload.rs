@@ -0,0 +1,69 @@
+mod settings {
+    include!("../src/settings.rs");
+}
+mod discovery {
+    include!("../src/discovery.rs");
+}
+mod daemon {
+    include!("../src/daemon.rs");
+}
+
+use daemon::{DaemonMode, ReloadAction, ReloadReport};
+use settings::ReloadMethod;
+
+fn run(args: &[&str]) -> ReloadAction {
+    ReloadAction::Run(args.iter().map(|a| a.to_string()).collect())
+}
+
+#[test]
+fn reload_command_follows_the_configured_method() {
+    let systemd = DaemonMode::SystemdUnit("swhkd.service".to_string());
+    let user = DaemonMode::Process(41);
+    let root = DaemonMode::SwhksServer { swhkd_pid: 42 };
+    let other = DaemonMode::Privileged(43);
+
+    let action = daemon::reload_action;
+    assert_eq!(action(ReloadMethod::Auto, &systemd, Some(7)), run(&["systemctl", "--user", "reload-or-restart", "swhkd.service"]));
+    assert_eq!(action(ReloadMethod::Auto, &user, Some(41)), run(&["kill", "-HUP", "41"]));
+    assert_eq!(action(ReloadMethod::Auto, &root, Some(42)), run(&["pkexec", "kill", "-HUP", "42"]));
+    assert_eq!(action(ReloadMethod::Auto, &other, Some(43)), run(&["pkexec", "kill", "-HUP", "43"]));
+    assert_eq!(action(ReloadMethod::Auto, &DaemonMode::NotRunning, None), ReloadAction::NotRunning);
+
+    // Anything but `Auto` ignores how swhkd was found to run.
+    assert_eq!(action(ReloadMethod::Systemd, &user, Some(41)), run(&["systemctl", "--user", "reload-or-restart", "swhkd.service"]));
+    assert_eq!(action(ReloadMethod::Signal, &root, Some(42)), run(&["kill", "-HUP", "42"]));
+    assert_eq!(action(ReloadMethod::Signal, &systemd, Some(7)), run(&["kill", "-HUP", "7"]));
+    assert_eq!(action(ReloadMethod::Pkexec, &user, Some(41)), run(&["pkexec", "kill", "-HUP", "41"]));
+    assert_eq!(action(ReloadMethod::Signal, &DaemonMode::NotRunning, None), ReloadAction::NotRunning);
+    assert_eq!(action(ReloadMethod::Pkexec, &systemd, None), ReloadAction::NotRunning);
+    assert_eq!(action(ReloadMethod::Manual, &user, Some(41)), ReloadAction::Skip);
+    assert_eq!(action(ReloadMethod::Manual, &DaemonMode::NotRunning, None), ReloadAction::Skip);
+}
+
+#[test]
+fn reload_summary_describes_the_outcome() {
+    let report = |method: &str, success: bool, stderr: &str| ReloadReport {
+        mode: DaemonMode::Process(41),
+        method: method.to_string(),
+        success,
+        stderr: stderr.to_string(),
+    };
+
+    assert_eq!(report("none", true, "").summary(), "Saved; swhkd was not reloaded");
+    assert_eq!(report("kill -HUP 41", true, "").summary(), "swhkd reloaded (process 41) via `kill -HUP 41`");
+    assert_eq!(report("kill -HUP 41", false, " \n").summary(), "swhkd reload failed (process 41) via `kill -HUP 41`");
+    assert_eq!(
+        report("kill -HUP 41", false, "kill: (41): Operation not permitted\n").summary(),
+        "swhkd reload failed (process 41) via `kill -HUP 41`: kill: (41): Operation not permitted"
+    );
+    let not_running = ReloadReport {
+        mode: DaemonMode::NotRunning,
+        method: "none".to_string(),
+        success: false,
+        stderr: "swhkd is not running; start it to use the saved config".to_string(),
+    };
+    assert_eq!(
+        not_running.summary(),
+        "swhkd reload failed (not running) via `none`: swhkd is not running; start it to use the saved config"
+    );
+}
@@ -0,0 +1,57 @@
+mod settings {
+    include!("../src/settings.rs");
+}
+mod discovery {
+    include!("../src/discovery.rs");
+}
+mod daemon {
+    include!("../src/daemon.rs");
+}
+
+use daemon::{ConfigState, ProcessInfo, SavedConfig};
+use std::fs;
+use std::path::PathBuf;
+
+fn swhkd(args: &[&str]) -> ProcessInfo {
+    ProcessInfo { pid: 41, uid: Some(0), cmdline: args.iter().map(|a| a.to_string()).collect() }
+}
+
+#[test]
+fn loaded_config_comes_from_the_command_line() {
+    let path = |args: &[&str]| daemon::loaded_config_path(&swhkd(args));
+    assert_eq!(path(&["swhkd", "-c", "/home/me/swhkdrc"]), PathBuf::from("/home/me/swhkdrc"));
+    assert_eq!(path(&["/usr/bin/swhkd", "--debug", "--config", "/tmp/a b"]), PathBuf::from("/tmp/a b"));
+    assert_eq!(path(&["swhkd", "--config=/etc/other"]), PathBuf::from("/etc/other"));
+    assert_eq!(path(&["swhkd", "--debug"]), PathBuf::from(discovery::SYSTEM_CONFIG));
+    assert_eq!(path(&["swhkd", "-c"]), PathBuf::from(discovery::SYSTEM_CONFIG));
+    assert_eq!(path(&[]), PathBuf::from(discovery::SYSTEM_CONFIG));
+    // The program name is never taken for an option.
+    assert_eq!(path(&["-c", "--debug"]), PathBuf::from(discovery::SYSTEM_CONFIG));
+}
+
+#[test]
+fn external_edits_after_a_save_are_reported() {
+    let dir = std::env::temp_dir().join(format!("swhkd-gui-daemon-status-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let config = dir.join("swhkdrc");
+    let other = dir.join("other");
+    let text = "super + a\n    firefox\n";
+    fs::write(&config, text).unwrap();
+    fs::write(&other, text).unwrap();
+    let saved = SavedConfig { path: config.clone(), hash: daemon::content_hash(text.as_bytes()) };
+
+    assert_eq!(daemon::config_state(&config, &config, None), ConfigState::NotSaved);
+    assert_eq!(daemon::config_state(&config, &config, Some(&saved)), ConfigState::Matches);
+    assert_eq!(daemon::config_state(&other, &config, Some(&saved)), ConfigState::OtherFile);
+    let elsewhere = SavedConfig { path: other.clone(), ..saved.clone() };
+    assert_eq!(daemon::config_state(&config, &config, Some(&elsewhere)), ConfigState::NotSaved);
+
+    fs::write(&config, "super + a\n    chromium\n").unwrap();
+    assert_eq!(daemon::config_state(&config, &config, Some(&saved)), ConfigState::Modified);
+    fs::write(&config, text).unwrap();
+    assert_eq!(daemon::config_state(&config, &config, Some(&saved)), ConfigState::Matches);
+
+    fs::remove_file(&config).unwrap();
+    assert_eq!(daemon::config_state(&config, &config, Some(&saved)), ConfigState::Missing);
+    fs::remove_dir_all(&dir).unwrap();
+}
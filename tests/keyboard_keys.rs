@@ -0,0 +1,42 @@
+mod data_model {
+    include!("../src/data_model.rs");
+}
+mod keyboard_map {
+    include!("../src/keyboard_map.rs");
+}
+
+use keyboard_map::{KeyboardLayout, RECORDED_KEYS};
+
+#[test]
+fn every_key_on_the_map_can_be_recorded_and_written() {
+    for layout in KeyboardLayout::ALL {
+        for cap in keyboard_map::rows(layout).into_iter().flatten().filter(|cap| !cap.is_modifier()) {
+            assert!(data_model::string_to_evdev_key(cap.key).is_ok(), "{layout}: {} can't be written", cap.key);
+            assert!(RECORDED_KEYS.iter().any(|(_, key)| *key == cap.key), "{layout}: {} can't be recorded", cap.key);
+        }
+    }
+    for (code, key) in RECORDED_KEYS {
+        assert!(data_model::string_to_evdev_key(key).is_ok(), "{code} records {key}, which can't be written");
+    }
+}
+
+#[test]
+fn key_codes_are_recorded_by_name() {
+    assert_eq!(keyboard_map::recorded_key("A"), Some("a"));
+    assert_eq!(keyboard_map::recorded_key("Key7"), Some("7"));
+    assert_eq!(keyboard_map::recorded_key("F1"), Some("f1"));
+    assert_eq!(keyboard_map::recorded_key("Snapshot"), Some("print"));
+    assert_eq!(keyboard_map::recorded_key("Capital"), Some("capslock"));
+    assert_eq!(keyboard_map::recorded_key("Backspace"), Some("backspace"));
+    assert_eq!(keyboard_map::recorded_key("PageUp"), Some("pageup"));
+    assert_eq!(keyboard_map::recorded_key("Right"), Some("right"));
+    // Modifiers on their own and keys with no swhkd name record nothing.
+    assert_eq!(keyboard_map::recorded_key("LShift"), None);
+    assert_eq!(keyboard_map::recorded_key("OEM102"), None);
+}
+
+#[test]
+fn both_layouts_have_rows_of_the_same_width() {
+    let widths = |layout| -> Vec<f32> { keyboard_map::rows(layout).iter().map(|row| row.iter().map(|cap| cap.width).sum()).collect() };
+    assert_eq!(widths(KeyboardLayout::Ansi), widths(KeyboardLayout::Iso));
+}
@@ -0,0 +1,293 @@
+mod data_model {
+    include!("../src/data_model.rs");
+}
+mod templates {
+    include!("../src/templates.rs");
+}
+mod undo {
+    include!("../src/undo.rs");
+}
+mod preflight {
+    include!("../src/preflight.rs");
+}
+mod runner {
+    include!("../src/runner.rs");
+}
+mod settings {
+    include!("../src/settings.rs");
+}
+
+use data_model::AppState;
+use templates::TEMPLATES;
+
+fn two_modes() -> AppState {
+    let mut state = AppState::default();
+    state.load_from_swhkd_config_str(
+        "super + t\n    alacritty\n\nsuper + b\n    firefox\n\nsuper + m\n    mpv\n\n\
+         mode resize\n\nsuper + b\n    brave\n\nendmode\n",
+    );
+    state
+}
+
+fn commands(state: &AppState, mode: usize) -> Vec<&str> {
+    state.modes[mode].hotkeys.iter().map(|hk| hk.action.command.as_str()).collect()
+}
+
+#[test]
+fn moving_hotkeys_skips_combos_bound_in_the_target_mode() {
+    let mut state = two_modes();
+    let skipped = state.transfer_hotkeys(0, &[2, 0, 1], 1, false).unwrap();
+    assert_eq!(skipped, vec!["super + b".to_string()]);
+    assert_eq!(commands(&state, 0), vec!["firefox"]);
+    assert_eq!(commands(&state, 1), vec!["brave", "alacritty", "mpv"]);
+
+    assert!(state.transfer_hotkeys(1, &[0], 1, false).is_err());
+    assert!(state.transfer_hotkeys(0, &[0], 5, false).is_err());
+}
+
+#[test]
+fn copying_keeps_the_original_and_inactive_rows_never_conflict() {
+    let mut state = two_modes();
+    state.modes[0].hotkeys[1].action.active = false;
+    let skipped = state.transfer_hotkeys(0, &[0, 1], 1, true).unwrap();
+    assert!(skipped.is_empty());
+    assert_eq!(commands(&state, 0), vec!["alacritty", "firefox", "mpv"]);
+    assert_eq!(commands(&state, 1), vec!["brave", "alacritty", "firefox"]);
+}
+
+#[test]
+fn the_same_combo_may_be_bound_once_per_mode() {
+    let mut state = two_modes();
+    assert!(state.check_duplicate_bindings().is_ok());
+    assert!(state.conflicting_signatures(0).is_empty());
+
+    let copy = state.modes[1].hotkeys[0].clone();
+    state.modes[1].hotkeys.push(copy);
+    let error = state.check_duplicate_bindings().unwrap_err();
+    assert!(error.contains("resize"), "{error}");
+    assert_eq!(state.conflicting_signatures(1).len(), 1);
+    assert!(state.conflicting_signatures(0).is_empty());
+}
+
+#[test]
+fn bulk_edits_only_touch_the_given_rows() {
+    let mut state = two_modes();
+    state.set_active(0, &[0, 2, 9], false);
+    let active: Vec<bool> = state.modes[0].hotkeys.iter().map(|hk| hk.action.active).collect();
+    assert_eq!(active, vec![false, true, false]);
+
+    state.add_tags(0, &[0, 1], &["apps".to_string(), "daily".to_string()]);
+    state.add_tags(0, &[1], &["apps".to_string()]);
+    state.remove_tag(0, &[0], "daily");
+    assert_eq!(state.modes[0].hotkeys[0].action.tags, vec!["apps"]);
+    assert_eq!(state.modes[0].hotkeys[1].action.tags, vec!["apps", "daily"]);
+    assert!(state.modes[1].hotkeys[0].action.tags.is_empty());
+
+    state.delete_hotkeys(0, &[0, 2]);
+    assert_eq!(commands(&state, 0), vec!["firefox"]);
+}
+
+#[test]
+fn reordering_carries_through_to_the_written_config() {
+    let mut state = two_modes();
+    state.reorder_hotkey(0, 2, 0);
+    assert_eq!(commands(&state, 0), vec!["mpv", "alacritty", "firefox"]);
+    state.reorder_hotkey(0, 0, 1);
+    assert_eq!(commands(&state, 0), vec!["alacritty", "mpv", "firefox"]);
+
+    state.selected_mode = 1;
+    state.reorder_mode(1, 0);
+    assert_eq!(state.selected_mode, 0);
+    assert_eq!(state.modes[0].name, "resize");
+
+    let text = state.to_swhkd_config_text();
+    assert!(text.find("mode resize").unwrap() < text.find("alacritty").unwrap());
+    assert!(text.find("alacritty").unwrap() < text.find("mpv").unwrap());
+
+    let mut reloaded = AppState::default();
+    reloaded.load_from_swhkd_config_str(&text);
+    assert_eq!(reloaded.modes, state.modes);
+}
+
+#[test]
+fn duplicated_and_template_modes_get_unique_names() {
+    let mut state = two_modes();
+    let copy = state.duplicate_mode(1).unwrap();
+    assert_eq!(copy, 2);
+    assert_eq!(state.modes[2].name, "resize_copy");
+    assert_eq!(state.modes[2].hotkeys, state.modes[1].hotkeys);
+    let second = state.duplicate_mode(1).unwrap();
+    assert_eq!(state.modes[second].name, "resize_copy_2");
+    assert_eq!(state.unique_mode_name("  RESIZE "), "RESIZE_2");
+    assert_eq!(state.unique_mode_name(" New \t Mode "), "New_Mode");
+
+    for template in TEMPLATES {
+        let hotkeys = template.hotkeys();
+        assert!(!hotkeys.is_empty(), "{template}");
+        assert!(hotkeys.iter().all(|hk| hk.validation_error().is_none() && hk.action.description.is_some()));
+        let idx = state.add_mode(template.name, hotkeys);
+        assert_eq!(state.modes[idx].name, template.name.replace(' ', "_"));
+    }
+    let again = state.add_mode("Screenshot", Vec::new());
+    assert_eq!(state.modes[again].name, "Screenshot_2");
+    assert!(state.check_duplicate_bindings().is_ok());
+}
+
+#[test]
+fn duplicated_and_template_modes_survive_saving() {
+    let mut state = two_modes();
+    let copy = state.duplicate_mode(1).unwrap();
+    state.duplicate_mode(copy).unwrap();
+    state.add_mode(TEMPLATES[0].name, TEMPLATES[0].hotkeys());
+    state.add_mode("New Mode", Vec::new());
+
+    let text = state.to_swhkd_config_text();
+    assert_eq!(preflight::check_config_text(&text, std::path::Path::new("/tmp/swhkdrc"), None), Ok(()));
+    let mut reloaded = AppState::default();
+    reloaded.load_from_swhkd_config_str(&text);
+    let names: Vec<&str> = reloaded.modes.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, ["Default", "resize", "resize_copy", "resize_copy_copy", "Window_management", "New_Mode"]);
+    assert_eq!(reloaded.modes[2].hotkeys, state.modes[1].hotkeys);
+}
+
+#[test]
+fn deleting_modes_keeps_the_selection_on_the_same_mode() {
+    let mut state = two_modes();
+    let idx = state.add_mode("gaming", Vec::new());
+    state.selected_mode = idx;
+
+    // A mode before the selected one shifts the selection down with it.
+    assert_eq!(state.delete_mode(0).unwrap().name, "Default");
+    assert_eq!(state.modes[state.selected_mode].name, "gaming");
+
+    // Deleting the selected last mode selects the one before it.
+    state.delete_mode(state.selected_mode);
+    assert_eq!(state.selected_mode, 0);
+    assert_eq!(state.modes[0].name, "resize");
+    assert!(state.delete_mode(5).is_none());
+
+    // The last mode is replaced by an empty "Default" mode.
+    state.delete_mode(0);
+    assert_eq!(state.modes.len(), 1);
+    assert_eq!(state.modes[0].name, "Default");
+    assert!(state.modes[0].hotkeys.is_empty());
+    assert_eq!(state.selected_mode, 0);
+}
+
+#[test]
+fn loaded_state_always_has_a_valid_selected_mode() {
+    let empty = r#"{ "version": 2, "modes": [], "selected_mode": 3, "recording_hotkey": null, "last_backup": null }"#;
+    let state = AppState::from_json(empty).unwrap();
+    assert_eq!(state.modes.len(), 1);
+    assert_eq!(state.selected_mode, 0);
+
+    let mut state = two_modes();
+    state.selected_mode = 7;
+    let json = serde_json::to_string(&state).unwrap();
+    assert_eq!(AppState::from_json(&json).unwrap().selected_mode, 1);
+
+    state.load_from_swhkd_config_str("");
+    assert_eq!(state.modes.len(), 1);
+    assert_eq!(state.selected_mode, 0);
+}
+
+#[test]
+fn undoing_a_move_keeps_edits_made_after_it() {
+    let mut state = two_modes();
+    let before = state.modes.clone();
+    state.transfer_hotkeys(0, &[0, 2], 1, false).unwrap();
+    let after = state.modes.clone();
+
+    // Edits Undo doesn't record: the moved row, another row, the order of the
+    // target mode and a new mode.
+    state.modes[1].hotkeys[1].action.command = "foot".to_string();
+    state.modes[0].hotkeys[0].action.description = Some("Browser".to_string());
+    state.reorder_hotkey(1, 2, 0);
+    state.add_mode("gaming", Vec::new());
+
+    undo::revert(&mut state, &before, &after);
+    assert_eq!(commands(&state, 0), vec!["foot", "firefox", "mpv"]);
+    assert_eq!(state.modes[0].hotkeys[1].action.description.as_deref(), Some("Browser"));
+    assert_eq!(commands(&state, 1), vec!["brave"]);
+    assert_eq!(state.modes[2].name, "gaming");
+}
+
+#[test]
+fn undoing_a_copy_or_bulk_edit_keeps_later_edits() {
+    let mut state = two_modes();
+    let before = state.modes.clone();
+    state.transfer_hotkeys(0, &[0], 1, true).unwrap();
+    let after = state.modes.clone();
+    state.modes[0].hotkeys[0].action.command = "kitty".to_string();
+    undo::revert(&mut state, &before, &after);
+    assert_eq!(commands(&state, 0), vec!["kitty", "firefox", "mpv"]);
+    assert_eq!(commands(&state, 1), vec!["brave"]);
+
+    let before = state.modes.clone();
+    state.set_active(0, &[0, 1], false);
+    state.add_tags(0, &[0, 1], &["apps".to_string()]);
+    let after = state.modes.clone();
+    // Reactivated by hand and tagged again since: both stay.
+    state.modes[0].hotkeys[1].action.active = true;
+    state.modes[0].hotkeys[0].action.tags.push("daily".to_string());
+    state.modes[0].hotkeys[0].action.command = "foot".to_string();
+    undo::revert(&mut state, &before, &after);
+    let rows: Vec<(&str, bool, Vec<String>)> = state.modes[0]
+        .hotkeys
+        .iter()
+        .map(|hk| (hk.action.command.as_str(), hk.action.active, hk.action.tags.clone()))
+        .collect();
+    assert_eq!(
+        rows,
+        vec![
+            ("foot", true, vec!["daily".to_string()]),
+            ("firefox", true, Vec::new()),
+            ("mpv", true, Vec::new()),
+        ]
+    );
+}
+
+#[test]
+fn undoing_a_deletion_keeps_edits_made_after_it() {
+    let mut state = two_modes();
+    let before = state.modes.clone();
+    state.delete_hotkeys(0, &[1]);
+    let after = state.modes.clone();
+    state.modes[0].hotkeys[1].action.command = "vlc".to_string();
+    state.modes[1].hotkeys[0].action.active = false;
+    undo::revert(&mut state, &before, &after);
+    assert_eq!(commands(&state, 0), vec!["alacritty", "firefox", "vlc"]);
+    assert!(!state.modes[1].hotkeys[0].action.active);
+
+    // A deleted mode comes back in its place, with its rows.
+    state.selected_mode = 1;
+    let before = state.modes.clone();
+    state.delete_mode(0);
+    let after = state.modes.clone();
+    state.modes[0].name = "resize more".to_string();
+    undo::revert(&mut state, &before, &after);
+    assert_eq!(state.modes.len(), 2);
+    assert_eq!(commands(&state, 0), vec!["alacritty", "firefox", "vlc"]);
+    assert_eq!(state.modes[1].name, "resize more");
+    assert_eq!(state.modes[state.selected_mode].name, "resize more");
+
+    // Deleting the last mode leaves "Default"; it only goes away on undo
+    // while nothing has been added to it.
+    let mut state = two_modes();
+    state.delete_mode(1);
+    let before = state.modes.clone();
+    state.delete_mode(0);
+    let after = state.modes.clone();
+    undo::revert(&mut state, &before, &after);
+    assert_eq!(state.modes, before);
+
+    state.delete_mode(0);
+    let after = state.modes.clone();
+    state.modes[0].hotkeys.push(two_modes().modes[1].hotkeys[0].clone());
+    undo::revert(&mut state, &before, &after);
+    assert_eq!(state.modes.len(), 2);
+    assert_eq!(commands(&state, 0), vec!["alacritty", "firefox", "mpv"]);
+    assert_eq!(commands(&state, 1), vec!["brave"]);
+    // The name is taken by the "Default" mode that now has a row.
+    assert_eq!(state.modes[0].name, "Default_2");
+}
@@ -3,12 +3,14 @@ mod data_model {
     include!("../src/data_model.rs");
 }
 
-use data_model::AppState;
+use data_model::{AppState, EnvVar, Include};
+use std::collections::BTreeSet;
 
 #[test]
 fn gui_can_load_all_sweet_sample_configs() {
     let files = [
         "tests/sweet_samples/basic_keybind.skwhrc",
+        "tests/sweet_samples/modes.swhkdrc",
 
     ];
     for path in files {
@@ -26,3 +28,149 @@ fn gui_can_load_all_sweet_sample_configs() {
         }
     }
 }
+
+#[test]
+fn descriptions_and_tags_round_trip_through_swhkdrc() {
+    let mut state = AppState::default();
+    state.modes[0].hotkeys.push(data_model::GuiHotkey::new(
+        ["super".to_string()].into_iter().collect(),
+        "enter".to_string(),
+        data_model::GuiAction {
+            command: "alacritty".to_string(),
+            active: true,
+            layer_id: 0,
+            description: Some("Open a terminal".to_string()),
+            tags: vec!["apps".to_string(), "terminal".to_string()],
+            env: Vec::new(),
+            working_dir: None,
+        },
+    ));
+
+    let path = std::env::temp_dir().join(format!("swhkd-gui-roundtrip-{}", std::process::id()));
+    state.save_to_custom_path(path.to_str().unwrap()).unwrap();
+
+    let mut loaded = AppState::default();
+    loaded.load_from_swhkd_config_at(path.to_str().unwrap()).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let hk = &loaded.modes[0].hotkeys[0];
+    assert_eq!(hk.combo_string(), "super + enter");
+    assert_eq!(hk.action.description.as_deref(), Some("Open a terminal"));
+    assert_eq!(hk.action.tags, ["apps", "terminal"]);
+}
+
+#[test]
+fn mode_blocks_load_into_separate_modes() {
+    let mut state = AppState::default();
+    state.load_from_swhkd_config_at("tests/sweet_samples/modes.swhkdrc").unwrap();
+
+    let names: Vec<_> = state.modes.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, ["Default", "music"]);
+    assert_eq!(state.modes[0].hotkeys.len(), 2);
+    assert_eq!(state.modes[1].hotkeys[0].action.command, "mpc next");
+
+    let text = state.to_swhkd_config_text();
+    assert!(text.contains("mode music\n\nctrl + n\n    mpc next\n\nendmode\n"));
+}
+
+#[test]
+fn environment_and_working_directory_become_an_env_wrapper() {
+    let env = vec![
+        EnvVar { name: "WAYLAND_DISPLAY".to_string(), value: "wayland-1".to_string() },
+        EnvVar { name: "PATH".to_string(), value: "$HOME/bin:$PATH".to_string() },
+        EnvVar { name: "GREETING".to_string(), value: r#"say "hi" \o/"#.to_string() },
+    ];
+    let line = data_model::wrap_command("notify-send 'it works' | cat", &env, Some("~/my projects"));
+    assert_eq!(
+        line,
+        r#"env WAYLAND_DISPLAY=wayland-1 PATH="$HOME/bin:$PATH" GREETING="say \"hi\" \\o/" sh -c 'cd "$HOME/my projects" && notify-send '\''it works'\'' | cat'"#
+    );
+    assert_eq!(
+        data_model::unwrap_command(&line),
+        ("notify-send 'it works' | cat".to_string(), env.clone(), Some("$HOME/my projects".to_string()))
+    );
+
+    assert_eq!(data_model::wrap_command("foot", &[], Some("/tmp")), "sh -c 'cd /tmp && foot'");
+    assert_eq!(data_model::wrap_command("foot", &env[..1], None), "env WAYLAND_DISPLAY=wayland-1 sh -c 'foot'");
+    assert_eq!(data_model::wrap_command("foot", &[], Some("  ")), "foot");
+    assert_eq!(data_model::wrap_command("foot", &[], Some("~")), "sh -c 'cd \"$HOME\" && foot'");
+    assert_eq!(data_model::wrap_command("foot", &[], Some("~bob/x")), "sh -c 'cd \"~bob/x\" && foot'");
+    for plain in ["sh -c 'foot'", "env -i foot", "env FOO=1 foot"] {
+        assert_eq!(data_model::unwrap_command(plain), (plain.to_string(), Vec::new(), None));
+    }
+
+    let mut state = AppState::default();
+    state.load_from_swhkd_config_str(&format!("super + t\n    {line}\n"));
+    let action = &state.modes[0].hotkeys[0].action;
+    assert_eq!(action.command, "notify-send 'it works' | cat");
+    assert_eq!(action.env, env);
+    assert_eq!(action.working_dir.as_deref(), Some("$HOME/my projects"));
+    assert!(state.to_swhkd_config_text().contains(&line));
+}
+
+#[test]
+fn environment_values_survive_saving_and_loading_unchanged() {
+    let value = r#"it's "$USER" `date` \ done"#;
+    let mut state = AppState::default();
+    let mut action = data_model::GuiAction {
+        command: "printf '%s|' \"$GREETING\"; pwd".to_string(),
+        env: vec![EnvVar { name: "GREETING".to_string(), value: value.to_string() }],
+        working_dir: Some(r#"/tmp/it's "odd""#.to_string()),
+        ..Default::default()
+    };
+    let hotkey = data_model::GuiHotkey::new(["super".to_string()].into_iter().collect(), "g".to_string(), action.clone());
+    state.modes[0].hotkeys.push(hotkey);
+
+    let path = std::env::temp_dir().join(format!("swhkd-gui-env-{}", std::process::id()));
+    state.save_to_custom_path(path.to_str().unwrap()).unwrap();
+    let mut loaded = AppState::default();
+    loaded.load_from_swhkd_config_at(path.to_str().unwrap()).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(loaded.modes[0].hotkeys[0].action, action);
+
+    // The shell sees the same value and directory.
+    let dir = std::env::temp_dir().join(format!("swhkd-gui-env-dir-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    action.working_dir = Some(dir.display().to_string());
+    action.env[0].value = r#"it's "quoted" `not run` \ back"#.to_string();
+    let output = std::process::Command::new("sh").arg("-c").arg(action.shell_command()).output().unwrap();
+    let _ = std::fs::remove_dir(&dir);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), format!("it's \"quoted\" `not run` \\ back|{}\n", dir.display()));
+
+    action.env[0].value = "two\nlines".to_string();
+    let hk = data_model::GuiHotkey::new(BTreeSet::new(), "g".to_string(), action);
+    assert_eq!(hk.validation_error().as_deref(), Some("The value of GREETING has a line break"));
+}
+
+#[test]
+fn include_lines_are_kept_in_their_block_when_saving() {
+    let config = "include ~/.config/swhkd/media.swhkdrc\n\nsuper + t\n    foot\n\nmode gaming\nimport games.swhkdrc\nsuper + s\n    steam\nendmode\n";
+    let mut state = AppState::default();
+    state.load_from_swhkd_config_str(config);
+    let include = |mode: Option<&str>, line: &str| Include { mode: mode.map(str::to_string), line: line.to_string() };
+    assert_eq!(
+        state.includes,
+        [include(None, "include ~/.config/swhkd/media.swhkdrc"), include(Some("gaming"), "import games.swhkdrc")]
+    );
+    assert_eq!(state.modes.len(), 2);
+    assert!(state.modes.iter().flat_map(|m| &m.hotkeys).all(|hk| !hk.key.contains("include")));
+
+    let text = state.to_swhkd_config_text();
+    assert!(text.starts_with("include ~/.config/swhkd/media.swhkdrc\n\nsuper + t\n"), "{text}");
+    assert!(text.contains("mode gaming\n\nimport games.swhkdrc\n\nsuper + s\n    steam\n\nendmode\n"), "{text}");
+    let mut reloaded = AppState::default();
+    reloaded.load_from_swhkd_config_str(&text);
+    assert_eq!(reloaded.includes, state.includes);
+    assert_eq!(reloaded.modes, state.modes);
+
+    // The block stays when its mode is gone, so the included bindings still
+    // belong to "gaming".
+    reloaded.modes.retain(|m| m.name != "gaming");
+    let text = reloaded.to_swhkd_config_text();
+    assert!(text.ends_with("mode gaming\n\nimport games.swhkdrc\n\nendmode\n\n"), "{text}");
+
+    // Loading another config doesn't carry the old includes over.
+    reloaded.load_from_swhkd_config_str("super + t\n    foot\n");
+    assert!(reloaded.includes.is_empty());
+    assert!(!reloaded.to_swhkd_config_text().contains("include"));
+}
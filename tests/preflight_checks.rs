@@ -0,0 +1,98 @@
+mod settings {
+    include!("../src/settings.rs");
+}
+mod preflight {
+    include!("../src/preflight.rs");
+}
+mod runner {
+    include!("../src/runner.rs");
+}
+
+use settings::AppSettings;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn invalid_configs_are_rejected() {
+    assert_eq!(preflight::check_config_text("super + a\n    firefox\n", Path::new("/tmp/swhkdrc"), None), Ok(()));
+
+    let error = preflight::check_config_text("super + + a\n    firefox\n", Path::new("/tmp/swhkdrc"), None).unwrap_err();
+    assert!(error.starts_with("Config rejected by the swhkd parser"), "{error}");
+    let error = preflight::check_config_text("super + notakey\n    firefox\n", Path::new("/tmp/swhkdrc"), None).unwrap_err();
+    assert!(error.starts_with("Config rejected by the swhkd parser"), "{error}");
+}
+
+#[test]
+fn the_extra_checker_sees_a_private_copy_and_can_veto() {
+    let dir = std::env::temp_dir().join(format!("swhkd-gui-checker-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let checker = dir.join("check.sh");
+    let seen = dir.join("seen");
+    fs::write(
+        &checker,
+        format!(
+            "#!/bin/sh\n{{ echo \"$1\"; stat -c %a \"$1\" \"$(dirname \"$1\")\"; }} > {seen}\ngrep -q forbidden \"$1\" && {{ echo 'forbidden command' >&2; exit 1; }}\nexit 0\n",
+            seen = seen.display()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&checker, fs::Permissions::from_mode(0o755)).unwrap();
+
+    assert_eq!(preflight::check_config_text("super + a\n    firefox\n", &dir.join("swhkdrc"), Some(&checker)), Ok(()));
+    let report = fs::read_to_string(&seen).unwrap();
+    let (candidate, modes) = report.split_once('\n').unwrap();
+    assert_eq!(modes, "600\n700\n");
+    // The candidate and its directory are gone once the check is done.
+    let candidate = PathBuf::from(candidate);
+    assert!(!candidate.exists());
+    assert!(!candidate.parent().unwrap().exists());
+    let error = preflight::check_config_text("super + a\n    forbidden\n", &dir.join("swhkdrc"), Some(&checker)).unwrap_err();
+    assert!(error.contains("forbidden command"), "{error}");
+    assert!(error.contains("nothing was saved"), "{error}");
+
+    let settings = AppSettings { check_command: Some(checker.clone()), ..AppSettings::default() };
+    if std::env::var_os(preflight::CHECK_COMMAND_ENV).is_none() {
+        assert_eq!(preflight::configured_checker(&settings), Some(checker));
+        assert_eq!(preflight::configured_checker(&AppSettings::default()), None::<PathBuf>);
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn relative_includes_are_checked_next_to_the_config() {
+    let dir = std::env::temp_dir().join(format!("swhkd-gui-includes-{}", std::process::id()));
+    fs::create_dir_all(dir.join("parts")).unwrap();
+    fs::write(dir.join("parts/media.swhkdrc"), "super + m\n    mpv\n").unwrap();
+    let config = dir.join("swhkdrc");
+    let text = "include parts/media.swhkdrc\n\nsuper + a\n    firefox\n\nmode resize\ninclude parts/media.swhkdrc\nendmode\n";
+
+    assert_eq!(preflight::check_config_text(text, &config, None), Ok(()));
+    // The same include next to a config without that file is a broken config.
+    let error = preflight::check_config_text(text, &std::env::temp_dir().join("elsewhere/swhkdrc"), None).unwrap_err();
+    assert!(error.starts_with("Config rejected by the swhkd parser"), "{error}");
+
+    // The checker sees the include as an absolute path.
+    let checker = dir.join("check.sh");
+    let seen = dir.join("seen");
+    fs::write(&checker, format!("#!/bin/sh\ngrep include \"$1\" > {}\n", seen.display())).unwrap();
+    fs::set_permissions(&checker, fs::Permissions::from_mode(0o755)).unwrap();
+    assert_eq!(preflight::check_config_text(text, &config, Some(&checker)), Ok(()));
+    let include = format!("include {}", dir.join("parts/media.swhkdrc").display());
+    assert_eq!(fs::read_to_string(&seen).unwrap(), format!("{include}\n{include}\n"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn a_hanging_checker_is_stopped() {
+    let dir = std::env::temp_dir().join(format!("swhkd-gui-hanging-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let checker = dir.join("check.sh");
+    fs::write(&checker, "#!/bin/sh\nsleep 60\n").unwrap();
+    fs::set_permissions(&checker, fs::Permissions::from_mode(0o755)).unwrap();
+    let started = std::time::Instant::now();
+    let error = preflight::check_config_text("super + a\n    firefox\n", &dir.join("swhkdrc"), Some(&checker)).unwrap_err();
+    assert!(started.elapsed() < preflight::CHECK_TIMEOUT + std::time::Duration::from_secs(5));
+    assert!(error.contains("did not finish within"), "{error}");
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,172 @@
+mod data_model {
+    include!("../src/data_model.rs");
+}
+mod row_view {
+    include!("../src/row_view.rs");
+}
+
+use data_model::{AppMode, AppState, GuiAction, GuiHotkey};
+use row_view::{RowFilter, RowView, SortColumn};
+use std::collections::BTreeSet;
+
+fn hotkey(combo: &str, command: &str, active: bool, tags: &[&str]) -> GuiHotkey {
+    let mut parts: Vec<&str> = combo.split(" + ").collect();
+    let key = parts.pop().unwrap_or_default().to_string();
+    let action = GuiAction {
+        command: command.to_string(),
+        active,
+        tags: tags.iter().map(|t| t.to_string()).collect(),
+        ..GuiAction::default()
+    };
+    GuiHotkey::new(parts.into_iter().map(String::from).collect(), key, action)
+}
+
+fn state() -> AppState {
+    let mut state = AppState::default();
+    state.modes = vec![
+        AppMode::new(
+            "Default".to_string(),
+            vec![
+                hotkey("super + t", "foot", true, &["terminal"]),
+                hotkey("super + b", "Firefox", true, &["web"]),
+                hotkey("super + t", "alacritty", true, &["terminal"]),
+                hotkey("super + m", "mpv", false, &[]),
+                hotkey("super + ", "brave", true, &["web"]),
+            ],
+        ),
+        AppMode::new("resize".to_string(), vec![hotkey("super + b", "brave --incognito", true, &[])]),
+    ];
+    state.selected_mode = 0;
+    state
+}
+
+#[test]
+fn an_empty_view_shows_every_row_in_written_order() {
+    assert_eq!(RowView::default().visible_rows(&state()), vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn search_and_tag_filter_narrow_the_rows() {
+    let state = state();
+    let view = RowView { search: "  SUPER + T ".to_string(), ..RowView::default() };
+    assert_eq!(view.visible_rows(&state), vec![0, 2]);
+
+    let view = RowView { search: "brave".to_string(), tag_filter: Some("web".to_string()), ..RowView::default() };
+    assert_eq!(view.visible_rows(&state), vec![4]);
+
+    let view = RowView { tag_filter: Some("terminal".to_string()), ..RowView::default() };
+    assert_eq!(view.visible_rows(&state), vec![0, 2]);
+}
+
+#[test]
+fn row_filters_pick_inactive_conflicting_and_invalid_rows() {
+    let mut state = state();
+    let rows = |state: &AppState, filter| RowView { filter, ..RowView::default() }.visible_rows(state);
+    assert_eq!(rows(&state, RowFilter::Inactive), vec![3]);
+    assert_eq!(rows(&state, RowFilter::Conflicting), vec![0, 2]);
+    assert_eq!(rows(&state, RowFilter::Invalid), vec![4]);
+
+    // An inactive duplicate neither conflicts nor is listed as conflicting.
+    state.modes[0].hotkeys[2].action.active = false;
+    assert!(rows(&state, RowFilter::Conflicting).is_empty());
+}
+
+#[test]
+fn sorting_cycles_through_ascending_descending_and_off() {
+    let state = state();
+    let mut view = RowView::default();
+
+    view.toggle_sort(SortColumn::Command);
+    assert_eq!(view.sort, Some((SortColumn::Command, true)));
+    // Case-insensitive: alacritty, brave, Firefox, foot, mpv.
+    assert_eq!(view.visible_rows(&state), vec![2, 4, 1, 0, 3]);
+
+    view.toggle_sort(SortColumn::Command);
+    assert_eq!(view.sort, Some((SortColumn::Command, false)));
+    assert_eq!(view.visible_rows(&state), vec![3, 0, 1, 4, 2]);
+
+    view.toggle_sort(SortColumn::Command);
+    assert_eq!(view.sort, None);
+    assert_eq!(view.visible_rows(&state), vec![0, 1, 2, 3, 4]);
+
+    // Another column starts over at ascending; ties keep the written order.
+    view.toggle_sort(SortColumn::Combo);
+    view.toggle_sort(SortColumn::Active);
+    assert_eq!(view.sort, Some((SortColumn::Active, true)));
+    assert_eq!(view.visible_rows(&state), vec![3, 0, 1, 2, 4]);
+}
+
+#[test]
+fn sorting_applies_to_the_filtered_rows() {
+    let view = RowView {
+        tag_filter: Some("terminal".to_string()),
+        sort: Some((SortColumn::Command, true)),
+        ..RowView::default()
+    };
+    assert_eq!(view.visible_rows(&state()), vec![2, 0]);
+}
+
+#[test]
+fn rows_move_past_their_visible_neighbours() {
+    let state = state();
+    let view = RowView { tag_filter: Some("web".to_string()), ..RowView::default() };
+    let visible = view.visible_rows(&state);
+    assert_eq!(visible, vec![1, 4]);
+    assert_eq!(view.reorder_targets(&visible, 0), (None, Some(4)));
+    assert_eq!(view.reorder_targets(&visible, 1), (Some(1), None));
+}
+
+#[test]
+fn reordering_is_disabled_while_sorted() {
+    let state = state();
+    let mut view = RowView::default();
+    view.toggle_sort(SortColumn::Combo);
+    let visible = view.visible_rows(&state);
+    for pos in 0..visible.len() {
+        assert_eq!(view.reorder_targets(&visible, pos), (None, None));
+    }
+
+    view.toggle_sort(SortColumn::Combo);
+    view.toggle_sort(SortColumn::Combo);
+    let visible = view.visible_rows(&state);
+    assert_eq!(view.reorder_targets(&visible, 2), (Some(1), Some(3)));
+}
+
+#[test]
+fn search_lists_matches_from_the_other_modes() {
+    let mut state = state();
+    let view = RowView { search: "brave".to_string(), ..RowView::default() };
+    let matches: Vec<(usize, &str)> =
+        view.other_mode_matches(&state).into_iter().map(|(mode, hk)| (mode, hk.action.command.as_str())).collect();
+    assert_eq!(matches, vec![(1, "brave --incognito")]);
+
+    // The selected mode is never listed, and an empty search lists nothing.
+    state.selected_mode = 1;
+    let matches: Vec<(usize, &str)> =
+        view.other_mode_matches(&state).into_iter().map(|(mode, hk)| (mode, hk.action.command.as_str())).collect();
+    assert_eq!(matches, vec![(0, "brave")]);
+    assert!(RowView { search: "  ".to_string(), ..RowView::default() }.other_mode_matches(&state).is_empty());
+}
+
+#[test]
+fn filtering_drops_hidden_rows_from_the_selection_before_a_bulk_delete() {
+    let mut state = state();
+    let mut selected: BTreeSet<usize> = [0, 1, 2, 3].into_iter().collect();
+    let mut anchor = Some(3);
+
+    let view = RowView { tag_filter: Some("terminal".to_string()), ..RowView::default() };
+    view.retain_visible(&state, &mut selected, &mut anchor);
+    assert_eq!(selected, [0, 2].into_iter().collect());
+    assert_eq!(anchor, None);
+
+    let rows: Vec<usize> = selected.into_iter().collect();
+    state.delete_hotkeys(0, &rows);
+    let commands: Vec<&str> = state.modes[0].hotkeys.iter().map(|hk| hk.action.command.as_str()).collect();
+    assert_eq!(commands, ["Firefox", "mpv", "brave"]);
+
+    // Without a search or filter the selection is kept as it is.
+    let mut selected: BTreeSet<usize> = [1].into_iter().collect();
+    let mut anchor = Some(1);
+    RowView::default().retain_visible(&state, &mut selected, &mut anchor);
+    assert_eq!((selected.len(), anchor), (1, Some(1)));
+}
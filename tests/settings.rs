@@ -0,0 +1,176 @@
+mod data_model {
+    include!("../src/data_model.rs");
+}
+mod cheatsheet {
+    include!("../src/cheatsheet.rs");
+}
+mod settings {
+    include!("../src/settings.rs");
+}
+mod cli {
+    include!("../src/cli.rs");
+}
+mod discovery {
+    include!("../src/discovery.rs");
+}
+mod daemon {
+    include!("../src/daemon.rs");
+}
+mod preflight {
+    include!("../src/preflight.rs");
+}
+mod profiles {
+    include!("../src/profiles.rs");
+}
+mod runner {
+    include!("../src/runner.rs");
+}
+
+use data_model::AppState;
+use discovery::ConfigSource;
+use settings::{AppSettings, ReloadMethod};
+use std::path::PathBuf;
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|a| a.to_string()).collect()
+}
+
+#[test]
+fn command_line_overrides_apply_on_top_of_saved_settings() {
+    let (overrides, rest) = cli::parse_overrides(&args(&[
+        "--config",
+        "/tmp/swhkdrc",
+        "--reload-method=manual",
+        "--backups",
+        "0",
+        "cheatsheet",
+        "--state",
+        "other.json",
+    ]))
+    .unwrap();
+    assert_eq!(rest, args(&["cheatsheet", "--state", "other.json"]));
+
+    let saved = AppSettings::default();
+    let effective = overrides.apply(&saved);
+    assert_eq!(effective.config_path, Some(PathBuf::from("/tmp/swhkdrc")));
+    assert_eq!(effective.reload_method, ReloadMethod::Manual);
+    assert_eq!(effective.backup_count, 0);
+    assert_eq!(effective.state_path, saved.state_path);
+
+    assert!(cli::parse_overrides(&args(&["--backups", "many"])).is_err());
+    assert!(cli::parse_overrides(&args(&["--config"])).is_err());
+}
+
+#[test]
+fn settings_files_missing_fields_use_defaults() {
+    let settings: AppSettings = serde_json::from_str(r#"{ "backup_count": 2 }"#).unwrap();
+    assert_eq!(settings.backup_count, 2);
+    assert_eq!(settings.reload_method, ReloadMethod::Auto);
+    assert_eq!(settings.config_path, None);
+}
+
+#[test]
+fn config_backups_are_rotated() {
+    let dir = std::env::temp_dir().join(format!("swhkd-gui-backups-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let config = dir.join("swhkdrc");
+
+    let mut state = AppState::default();
+    state.backup_config(&config, 2).unwrap();
+    assert!(state.last_backup.is_none(), "nothing to back up yet");
+
+    for round in 0..4 {
+        std::fs::write(&config, format!("# round {round}\n")).unwrap();
+        state.backup_config(&config, 2).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+    let backups = std::fs::read_dir(&dir).unwrap().count() - 1;
+    assert_eq!(backups, 2);
+    let newest = state.last_backup.clone().unwrap();
+    assert_eq!(std::fs::read_to_string(newest).unwrap(), "# round 3\n");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn config_discovery_checks_xdg_then_home_then_etc() {
+    let xdg = std::env::temp_dir().join(format!("swhkd-gui-xdg-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&xdg);
+    std::env::set_var("XDG_CONFIG_HOME", &xdg);
+
+    let sources: Vec<ConfigSource> = discovery::candidates().into_iter().map(|(_, source)| source).collect();
+    assert_eq!(sources.first(), Some(&ConfigSource::XdgConfigHome));
+    assert_eq!(sources.last(), Some(&ConfigSource::System));
+    assert_eq!(discovery::candidates().last().unwrap().0, PathBuf::from("/etc/swhkd/swhkdrc"));
+
+    std::fs::create_dir_all(xdg.join("swhkd")).unwrap();
+    std::fs::write(xdg.join("swhkd/swhkdrc"), "super + t\n    alacritty\n").unwrap();
+    let found = discovery::discover();
+    assert_eq!(found.path, xdg.join("swhkd/swhkdrc"));
+    assert_eq!(found.source, ConfigSource::XdgConfigHome);
+
+    let mut settings = AppSettings::default();
+    settings.config_path = Some(PathBuf::from("/tmp/other-swhkdrc"));
+    let located = discovery::locate(&settings, &settings::SettingsOverrides::default());
+    assert_eq!(located.source, ConfigSource::Settings);
+
+    std::fs::remove_dir_all(&xdg).unwrap();
+}
+
+#[test]
+fn privilege_is_only_needed_where_writing_fails() {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = std::env::temp_dir().join(format!("swhkd-gui-privilege-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let config = dir.join("swhkd/swhkdrc");
+    assert!(!discovery::needs_privilege(&config));
+    std::fs::write(dir.join("existing"), "").unwrap();
+    assert!(!discovery::needs_privilege(&dir.join("existing")));
+    // World-writable but owned by root, like /tmp.
+    assert!(!discovery::needs_privilege(&std::env::temp_dir().join(format!("swhkd-gui-new-{}/swhkdrc", std::process::id()))));
+    // The write test leaves nothing behind.
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+    std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o555)).unwrap();
+    let is_root = std::fs::write(dir.join("root-test"), "").is_ok();
+    assert_eq!(discovery::needs_privilege(&config), !is_root);
+    std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn profiles_are_saved_next_to_the_state_file() {
+    let dir = std::env::temp_dir().join(format!("swhkd-gui-profiles-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let profiles_dir = profiles::profiles_dir(&dir.join("state.json"));
+    assert_eq!(profiles_dir, dir.join("profiles"));
+    assert!(profiles::list(&profiles_dir).is_empty());
+
+    let mut gaming = AppState::default();
+    gaming.load_from_swhkd_config_str("super + g\n    steam\n");
+    profiles::save(&profiles_dir, "gaming", &gaming).unwrap();
+    profiles::save(&profiles_dir, "Coding", &AppState::default()).unwrap();
+    assert_eq!(profiles::list(&profiles_dir), args(&["Coding", "gaming"]));
+    assert_eq!(profiles::load(&profiles_dir, "gaming").unwrap().modes, gaming.modes);
+
+    assert!(profiles::load(&profiles_dir, "presentation").is_err());
+    assert!(profiles::save(&profiles_dir, "../escape", &gaming).is_err());
+    assert!(profiles::save(&profiles_dir, " ", &gaming).is_err());
+
+    // `apply --profile` writes the profile's bindings without reloading.
+    let config = dir.join("swhkdrc");
+    let settings = AppSettings {
+        config_path: Some(config.clone()),
+        state_path: dir.join("state.json"),
+        reload_method: ReloadMethod::Manual,
+        backup_count: 0,
+        ..AppSettings::default()
+    };
+    assert_eq!(cli::run(&args(&["apply", "--profile", "gaming"]), &settings), 0);
+    assert_eq!(std::fs::read_to_string(&config).unwrap(), gaming.to_swhkd_config_text());
+    assert_eq!(cli::run(&args(&["apply", "--profile", "missing"]), &settings), 1);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
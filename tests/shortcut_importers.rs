@@ -0,0 +1,75 @@
+mod data_model {
+    include!("../src/data_model.rs");
+}
+mod importers {
+    include!("../src/importers.rs");
+}
+mod config_import {
+    include!("../src/config_import.rs");
+}
+
+use config_import::{ConflictPolicy, ImportOutcome, ImportPlan, ImportTarget};
+use data_model::{AppMode, AppState};
+use importers::{import_shortcuts_file, parse_accelerator, parse_kde_shortcut};
+
+fn combo(mods: &[&str], key: &str) -> Option<(std::collections::BTreeSet<String>, String)> {
+    Some((mods.iter().map(|m| m.to_string()).collect(), key.to_string()))
+}
+
+#[test]
+fn gnome_accelerators_translate_to_gui_combos() {
+    assert_eq!(parse_accelerator("<Super><Shift>Return"), combo(&["shift", "super"], "enter"));
+    assert_eq!(parse_accelerator("<Primary>t"), combo(&["ctrl"], "t"));
+    assert_eq!(parse_accelerator("<Alt>Page_Up"), combo(&["alt"], "pageup"));
+    assert_eq!(parse_accelerator("disabled"), None);
+    assert_eq!(parse_accelerator("<Super>"), None);
+}
+
+#[test]
+fn kde_shortcuts_translate_to_gui_combos() {
+    assert_eq!(parse_kde_shortcut("Meta+Shift+Return"), combo(&["shift", "super"], "enter"));
+    assert_eq!(parse_kde_shortcut("Ctrl+Alt+T"), combo(&["alt", "ctrl"], "t"));
+    assert_eq!(parse_kde_shortcut("Ctrl++"), combo(&["ctrl"], "plus"));
+    assert_eq!(parse_kde_shortcut("none"), None);
+}
+
+#[test]
+fn gui_can_import_sample_shortcut_exports() {
+    let gnome = import_shortcuts_file("tests/shortcut_exports/gnome_custom_keybindings.ini").unwrap();
+    assert_eq!(gnome.len(), 2);
+    assert_eq!(gnome[1].action.command, "nautilus --new-window");
+
+    let kde = import_shortcuts_file("tests/shortcut_exports/kglobalshortcutsrc").unwrap();
+    let commands: Vec<_> = kde.iter().map(|hk| hk.action.command.as_str()).collect();
+    assert_eq!(commands, ["gtk-launch org.kde.konsole", "gtk-launch org.kde.dolphin", "gtk-launch org.kde.dolphin"]);
+
+    let khotkeys = import_shortcuts_file("tests/shortcut_exports/terminal.khotkeys").unwrap();
+    assert_eq!(khotkeys.len(), 1);
+    assert_eq!(khotkeys[0].key, "enter");
+    assert_eq!(khotkeys[0].action.command, "konsole --workdir /tmp");
+}
+
+#[test]
+fn imported_shortcuts_go_through_the_conflict_policy() {
+    let mut state = AppState::default();
+    state.load_from_swhkd_config_str("super + shift + enter\n    foot\n\nmode media\nsuper + m\n    mpv\nendmode\n");
+    state.selected_mode = 0;
+    let imported = import_shortcuts_file("tests/shortcut_exports/gnome_custom_keybindings.ini").unwrap();
+
+    // Built the way the GUI does for the selected mode.
+    let mut plan = ImportPlan::new(vec![AppMode::new(state.modes[0].name.clone(), imported)], &state.modes);
+    plan.targets = vec![ImportTarget::Existing(state.selected_mode)];
+    let result = plan.apply(&state);
+    let outcomes: Vec<_> = result.rows.iter().map(|r| (r.command.as_str(), r.outcome)).collect();
+    assert_eq!(outcomes, [("alacritty", ImportOutcome::Skipped), ("nautilus --new-window", ImportOutcome::Added)]);
+    let commands: Vec<_> = result.modes[0].hotkeys.iter().map(|hk| hk.action.command.as_str()).collect();
+    assert_eq!(commands, ["foot", "nautilus --new-window"]);
+    assert_eq!(result.modes[1], state.modes[1]);
+    assert!(AppState { modes: result.modes, ..state.clone() }.check_duplicate_bindings().is_ok());
+
+    plan.policy = ConflictPolicy::KeepBoth;
+    let result = plan.apply(&state);
+    assert_eq!(result.modes[0].hotkeys.len(), 3);
+    assert!(!result.modes[0].hotkeys[1].action.active);
+    assert!(AppState { modes: result.modes, ..state }.check_duplicate_bindings().is_ok());
+}
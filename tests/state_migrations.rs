@@ -0,0 +1,100 @@
+mod data_model {
+    include!("../src/data_model.rs");
+}
+
+use data_model::{AppState, STATE_VERSION};
+
+// Saved by versions before the state file carried a version number.
+const UNVERSIONED_STATE: &str = r#"{
+  "modes": [
+    {
+      "name": "Default",
+      "hotkeys": [
+        {
+          "modifiers": ["super"],
+          "key": "t",
+          "action": { "command": "alacritty", "active": true, "layer_id": 0 }
+        }
+      ]
+    }
+  ],
+  "selected_mode": 0,
+  "recording_hotkey": 3,
+  "last_backup": null
+}"#;
+
+#[test]
+fn unversioned_state_is_migrated() {
+    let state = AppState::from_json(UNVERSIONED_STATE).expect("old state should load");
+    assert_eq!(state.version, STATE_VERSION);
+    assert_eq!(state.recording_hotkey, None);
+    let hk = &state.modes[0].hotkeys[0];
+    assert_eq!(hk.action.command, "alacritty");
+    assert!(hk.action.tags.is_empty());
+}
+
+#[test]
+fn version_1_mode_names_become_swhkd_mode_names() {
+    let migrated = AppState::migrate_json(include_str!("state_files/v1.json")).unwrap();
+    let expected: serde_json::Value = serde_json::from_str(include_str!("state_files/v2.json")).unwrap();
+    assert_eq!(migrated, expected);
+
+    let state = AppState::from_json(include_str!("state_files/v1.json")).unwrap();
+    let text = state.to_swhkd_config_text();
+    assert!(text.contains("mode Game_Mode\n") && text.contains("mode game_mode_2\n"), "{text}");
+    // Already migrated files are left alone.
+    assert_eq!(AppState::migrate_json(include_str!("state_files/v2.json")).unwrap(), expected);
+}
+
+#[test]
+fn current_state_round_trips() {
+    let mut state = AppState::from_json(UNVERSIONED_STATE).unwrap();
+    state.modes[0].hotkeys[0].action.tags.push("apps".to_string());
+    let json = serde_json::to_string(&state).unwrap();
+    let loaded = AppState::from_json(&json).unwrap();
+    assert_eq!(loaded.version, STATE_VERSION);
+    assert_eq!(loaded.modes, state.modes);
+}
+
+#[test]
+fn unreadable_state_is_an_error_and_can_be_set_aside() {
+    assert!(AppState::from_json("{ not json").is_err());
+    assert!(AppState::from_json(r#"{ "version": 999, "modes": [] }"#).is_err());
+    assert!(AppState::from_json(r#"{ "version": 2, "modes": "oops" }"#).is_err());
+
+    let path = std::env::temp_dir().join(format!("swhkd-gui-corrupt-{}.json", std::process::id()));
+    std::fs::write(&path, "{ not json").unwrap();
+    let path = path.display().to_string();
+    assert!(AppState::load_from_json_file(&path).is_err());
+
+    let aside = AppState::set_aside_state_file(&path).unwrap();
+    assert!(!std::path::Path::new(&path).exists());
+    assert_eq!(std::fs::read_to_string(&aside).unwrap(), "{ not json");
+    std::fs::remove_file(aside).unwrap();
+}
+
+#[test]
+fn missing_state_file_starts_fresh() {
+    let state = AppState::load_from_json_file("/nonexistent/swhkd-gui/state.json").unwrap();
+    assert_eq!(state.version, STATE_VERSION);
+    assert_eq!(state.modes.len(), 1);
+}
+
+#[test]
+fn schema_errors_name_the_offending_field() {
+    let json = r#"{
+      "version": 2,
+      "modes": [{ "name": "Default", "hotkeys": [
+        { "modifiers": ["super"], "key": "t", "action": { "command": "alacritty", "active": "yes", "layer_id": 0 } }
+      ] }],
+      "selected_mode": 0,
+      "recording_hotkey": null,
+      "last_backup": null
+    }"#;
+    let error = AppState::from_json(json).unwrap_err();
+    assert!(error.contains("/modes/0/hotkeys/0/action/active"), "{error}");
+
+    let schema = AppState::json_schema();
+    assert!(schema["properties"]["modes"].is_object());
+    assert!(AppState::validate_state(&serde_json::to_value(AppState::default()).unwrap()).is_ok());
+}
@@ -0,0 +1,59 @@
+mod data_model {
+    include!("../src/data_model.rs");
+}
+
+use data_model::AppState;
+
+fn state(config: &str) -> AppState {
+    let mut state = AppState::default();
+    state.load_from_swhkd_config_str(config);
+    state
+}
+
+#[test]
+fn edits_missing_from_the_state_file_are_unsaved() {
+    let config = "super + t\n    foot\n";
+    let mut state = state(config);
+    let saved = state.modes.clone();
+    let on_disk = state.to_swhkd_config_text();
+    assert!(!state.has_unsaved_changes(&saved, Some(&on_disk)));
+
+    state.modes[0].hotkeys[0].action.command = "alacritty".to_string();
+    assert!(state.has_unsaved_changes(&saved, Some(&on_disk)));
+    state.modes[0].hotkeys[0].action.command = "foot".to_string();
+    assert!(!state.has_unsaved_changes(&saved, Some(&on_disk)));
+
+    // Runtime ids don't count: a reloaded copy of the same modes is saved.
+    let reloaded = self::state(config);
+    assert!(!reloaded.has_unsaved_changes(&saved, Some(&on_disk)));
+
+    state.add_mode("gaming", Vec::new());
+    assert!(state.has_unsaved_changes(&saved, Some(&on_disk)));
+}
+
+#[test]
+fn a_config_behind_the_state_is_unsaved() {
+    let mut state = state("super + t\n    foot\n");
+    state.modes[0].hotkeys[0].action.command = "alacritty".to_string();
+    // The state file is up to date (edits autosave it), swhkdrc isn't.
+    let saved = state.modes.clone();
+    assert!(state.has_unsaved_changes(&saved, Some("super + t\n    foot\n")));
+    assert!(state.has_unsaved_changes(&saved, None));
+    assert!(!state.has_unsaved_changes(&saved, Some(&state.to_swhkd_config_text())));
+
+    // Hand-written formatting and comments are not a difference.
+    assert!(!state.has_unsaved_changes(&saved, Some("# mine\nsuper+t\n\talacritty\n")));
+
+    // Inactive rows aren't written, so they don't make the config stale.
+    state.modes[0].hotkeys[0].action.active = false;
+    let saved = state.modes.clone();
+    assert!(!state.has_unsaved_changes(&saved, None));
+    assert!(!state.has_unsaved_changes(&saved, Some("")));
+
+    // An empty mode is written but reads back as nothing.
+    let mut empty = AppState::default();
+    empty.add_mode("gaming", Vec::new());
+    let saved = empty.modes.clone();
+    assert!(empty.has_unsaved_changes(&saved, None));
+    assert!(!empty.has_unsaved_changes(&saved, Some(&empty.to_swhkd_config_text())));
+}